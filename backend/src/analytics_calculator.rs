@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use log::{info, error};
 use scylla::Session;
 use scylla::query::Query;
@@ -6,7 +6,11 @@ use futures_util::stream::StreamExt;
 use std::time::Duration;
 use scylla::FromRow;
 
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
 use crate::get_game_stats::GameStats;
+use crate::stats_store::StatsStore;
 use crate::analytics_types::{PlayerSeasonAverages, PlayerSeasonPercentiles};
 
 /// Calculates average statistics for a given slice of GameStats,
@@ -224,10 +228,18 @@ pub fn calculate_averages_for_games(
 /// This function groups game stats by player and year, computes averages,
 /// and then persists them. It now leverages the `calculate_averages_for_games` helper.
 pub async fn calculate_and_insert_season_averages(
-    session: &Session,
+    store: &dyn StatsStore,
     all_game_stats: &[GameStats],
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("Calculating player season averages...");
+    let season_averages = calculate_season_averages(all_game_stats);
+    info!("Inserting {} player season average records", season_averages.len());
+    store.write_season_averages(&season_averages).await?;
+    Ok(())
+}
+
+/// Groups game stats by (pid, year, team) and averages each group.
+fn calculate_season_averages(all_game_stats: &[GameStats]) -> Vec<PlayerSeasonAverages> {
 
     // Group game stats by (pid, year, team)
     // HashMap<(pid, year, team), Vec<GameStats>>
@@ -268,32 +280,32 @@ pub async fn calculate_and_insert_season_averages(
         }
     }
 
-    info!("Inserting {} player season average records into ScyllaDB", season_averages.len());
+    season_averages
+}
+
+pub async fn insert_season_averages(
+    session: &Session,
+    season_averages: &[PlayerSeasonAverages],
+) -> Result<WriteReport, QueryError> {
     let query = r#"
-        INSERT INTO stats.player_season_avg_stats (
+        INSERT INTO player_season_avg_stats (
             pid, year, team, player_name, games_played, avg_min_per, avg_o_rtg, avg_usg, avg_e_fg, avg_ts_per, avg_orb_per, avg_drb_per, avg_ast_per, avg_to_per, avg_dunks_made, avg_dunks_att, avg_rim_made, avg_rim_att, avg_mid_made, avg_mid_att, avg_two_pm, avg_two_pa, avg_tpm, avg_tpa, avg_ftm, avg_fta, avg_bpm_rd, avg_obpm, avg_dbpm, avg_bpm_net, avg_pts, avg_orb, avg_drb, avg_ast, avg_tov, avg_stl, avg_blk, avg_stl_per, avg_blk_per, avg_pf, avg_possessions, avg_bpm, avg_sbpm, avg_inches, avg_opstyle, avg_quality, avg_win1, avg_win2
         ) VALUES (
             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
         )
     "#;
 
-    let prepared = session.prepare(query).await?;
-
-    for avg in season_averages {
-        session.execute(&prepared, &avg).await?;
-    }
-
-    Ok(())
+    write_batched(session, "player_season_avg_stats", query, season_averages, |avg| (avg.team.clone(), avg.year)).await
 }
 
 /// Fetches all player season average statistics from ScyllaDB.
 pub async fn get_all_player_season_averages_from_db(
     session: &Session,
-) -> Result<Vec<PlayerSeasonAverages>, Box<dyn std::error::Error>> {
+) -> Result<Vec<PlayerSeasonAverages>, QueryError> {
     info!("Fetching all player season averages from database...");
     let query_cql = r#"
         SELECT pid, year, team, player_name, games_played, avg_min_per, avg_o_rtg, avg_usg, avg_e_fg, avg_ts_per, avg_orb_per, avg_drb_per, avg_ast_per, avg_to_per, avg_dunks_made, avg_dunks_att, avg_rim_made, avg_rim_att, avg_mid_made, avg_mid_att, avg_two_pm, avg_two_pa, avg_tpm, avg_tpa, avg_ftm, avg_fta, avg_bpm_rd, avg_obpm, avg_dbpm, avg_bpm_net, avg_pts, avg_orb, avg_drb, avg_ast, avg_tov, avg_stl, avg_blk, avg_stl_per, avg_blk_per, avg_pf, avg_possessions, avg_bpm, avg_sbpm, avg_inches, avg_opstyle, avg_quality, avg_win1, avg_win2
-        FROM stats.player_season_avg_stats
+        FROM player_season_avg_stats
     "#;
 
     let mut all_averages = Vec::new();
@@ -321,7 +333,7 @@ pub async fn get_all_player_season_averages_from_db(
             },
             Err(e) => {
                 error!("Failed to retrieve row from query_iter (total processed: {}): {}", row_count, e);
-                return Err(Box::new(e));
+                return Err(e);
             }
         }
     }
@@ -348,6 +360,7 @@ pub async fn get_all_player_season_averages_from_db(
 /// Returns:
 /// - `Option<PlayerSeasonAverages>`: `Some` with the calculated averages for the slice,
 ///   `None` if no relevant games are found or not enough games for the slice after filtering for minutes.
+#[allow(dead_code)]
 pub fn calculate_last_x_games_averages(
     all_game_stats: &[GameStats],
     player_id: i32,
@@ -379,7 +392,7 @@ pub fn calculate_last_x_games_averages(
     let slice_games: Vec<&GameStats> = if player_games.len() > num_games {
         player_games.iter().copied().skip(player_games.len() - num_games).collect()
     } else {
-        player_games.iter().copied().collect() // Collect references if fewer games than num_games
+        player_games.to_vec() // Collect references if fewer games than num_games
     };
 
     if slice_games.is_empty() {
@@ -490,7 +503,7 @@ pub fn calculate_percentile(value: f64, sorted_data: &[f64]) -> f64 {
 
 /// Calculates and inserts player season percentile statistics into ScyllaDB.
 pub async fn calculate_and_insert_season_percentiles(
-    session: &Session,
+    store: &dyn StatsStore,
     all_season_averages: &[PlayerSeasonAverages],
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("Calculating player season percentiles...");
@@ -500,6 +513,25 @@ pub async fn calculate_and_insert_season_percentiles(
         return Ok(());
     }
 
+    let season_percentiles = calculate_season_percentiles(all_season_averages);
+    info!("Inserting {} player season percentile records", season_percentiles.len());
+    store.write_season_percentiles(&season_percentiles).await?;
+    Ok(())
+}
+
+/// Ranks every player season average against the others from the same season, so a
+/// player is never compared with another era's players.
+fn calculate_season_percentiles(all_season_averages: &[PlayerSeasonAverages]) -> Vec<PlayerSeasonPercentiles> {
+    let mut by_year: BTreeMap<i32, Vec<&PlayerSeasonAverages>> = BTreeMap::new();
+    for avg in all_season_averages {
+        by_year.entry(avg.year).or_default().push(avg);
+    }
+    by_year.values().flat_map(|season| rank_season_averages(season)).collect()
+}
+
+/// Ranks each of one season's averages against the rest of that season.
+fn rank_season_averages(all_season_averages: &[&PlayerSeasonAverages]) -> Vec<PlayerSeasonPercentiles> {
+
     // Collect all values for each statistical category
     let mut min_per_values = Vec::new();
     let mut o_rtg_values = Vec::new();
@@ -692,32 +724,32 @@ pub async fn calculate_and_insert_season_percentiles(
         });
     }
 
-    info!("Inserting {} player season percentile records into ScyllaDB", season_percentiles.len());
+    season_percentiles
+}
+
+pub async fn insert_season_percentiles(
+    session: &Session,
+    season_percentiles: &[PlayerSeasonPercentiles],
+) -> Result<WriteReport, QueryError> {
     let query = r#"
-        INSERT INTO stats.player_season_percentiles (
+        INSERT INTO player_season_percentiles (
             pid, year, team, player_name, pct_min_per, pct_o_rtg, pct_usg, pct_e_fg, pct_ts_per, pct_orb_per, pct_drb_per, pct_ast_per, pct_to_per, pct_dunks_made, pct_dunks_att, pct_rim_made, pct_rim_att, pct_mid_made, pct_mid_att, pct_two_pm, pct_two_pa, pct_tpm, pct_tpa, pct_ftm, pct_fta, pct_bpm_rd, pct_obpm, pct_dbpm, pct_bpm_net, pct_pts, pct_orb, pct_drb, pct_ast, pct_tov, pct_stl, pct_blk, pct_stl_per, pct_blk_per, pct_pf, pct_possessions, pct_bpm, pct_sbpm, pct_inches, pct_opstyle, pct_quality, pct_win1, pct_win2
         ) VALUES (
             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
         )
     "#;
 
-    let prepared = session.prepare(query).await?;
-
-    for pct in season_percentiles {
-        session.execute(&prepared, &pct).await?;
-    }
-
-    Ok(())
+    write_batched(session, "player_season_percentiles", query, season_percentiles, |pct| (pct.pid, pct.year)).await
 }
 
 /// Fetches all player season percentile statistics from ScyllaDB.
 pub async fn get_all_player_season_percentiles_from_db(
     session: &Session,
-) -> Result<Vec<PlayerSeasonPercentiles>, Box<dyn std::error::Error>> {
+) -> Result<Vec<PlayerSeasonPercentiles>, QueryError> {
     info!("Fetching all player season percentiles from database...");
     let query_cql = r#"
         SELECT pid, year, team, player_name, pct_min_per, pct_o_rtg, pct_usg, pct_e_fg, pct_ts_per, pct_orb_per, pct_drb_per, pct_ast_per, pct_to_per, pct_dunks_made, pct_dunks_att, pct_rim_made, pct_rim_att, pct_mid_made, pct_mid_att, pct_two_pm, pct_two_pa, pct_tpm, pct_tpa, pct_ftm, pct_fta, pct_bpm_rd, pct_obpm, pct_dbpm, pct_bpm_net, pct_pts, pct_orb, pct_drb, pct_ast, pct_tov, pct_stl, pct_blk, pct_stl_per, pct_blk_per, pct_pf, pct_possessions, pct_bpm, pct_sbpm, pct_inches, pct_opstyle, pct_quality, pct_win1, pct_win2
-        FROM stats.player_season_percentiles
+        FROM player_season_percentiles
     "#;
 
     let mut all_percentiles = Vec::new();
@@ -745,7 +777,7 @@ pub async fn get_all_player_season_percentiles_from_db(
             },
             Err(e) => {
                 error!("Failed to retrieve row from query_iter (total processed: {}): {}", row_count, e);
-                return Err(Box::new(e));
+                return Err(e);
             }
         }
     }
@@ -753,3 +785,105 @@ pub async fn get_all_player_season_percentiles_from_db(
     info!("Successfully fetched and parsed a total of {} player season percentile records.", all_percentiles.len());
     Ok(all_percentiles)
 }
+
+/// Recomputes season averages from stored games, then ranks them within their season.
+/// `seasons` limits which seasons are rebuilt and re-ranked; `None` rebuilds all of them.
+pub async fn recompute_season_analytics(
+    store: &dyn StatsStore,
+    seasons: Option<&[i32]>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let game_stats = match seasons {
+        Some(seasons) => {
+            let mut game_stats = Vec::new();
+            for &season in seasons {
+                game_stats.extend(store.season_games(season).await?);
+            }
+            info!("Recomputing averages from {} games in seasons {:?}", game_stats.len(), seasons);
+            game_stats
+        }
+        None => store.all_games().await?,
+    };
+
+    // Calculate and insert player season averages
+    info!("Starting player season average calculation...");
+    calculate_and_insert_season_averages(store, &game_stats).await?;
+    info!("Finished player season average calculation.");
+
+    // Calculate and insert player season percentiles
+    info!("Starting player season percentile calculation...");
+    // Re-rank only the recomputed seasons; others keep the percentiles they have.
+    let season_averages: Vec<PlayerSeasonAverages> = store
+        .season_averages()
+        .await?
+        .into_iter()
+        .filter(|avg| seasons.is_none_or(|seasons| seasons.contains(&avg.year)))
+        .collect();
+    calculate_and_insert_season_percentiles(store, &season_averages).await?;
+    info!("Finished player season percentile calculation.");
+
+    Ok(())
+}
+
+/// Rebuilds averages and percentiles for only the given (pid, year, team) player seasons,
+/// after an incremental game load touched them.
+///
+/// `games` must hold every stored game for those player seasons. New averages shift the
+/// distribution everyone in the season is ranked against, so percentiles are rewritten
+/// for every player in each season touched.
+pub async fn recompute_player_analytics(
+    store: &dyn StatsStore,
+    games: &[GameStats],
+    players: &HashSet<(i32, i32, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if players.is_empty() {
+        info!("No player seasons changed; averages and percentiles are up to date.");
+        return Ok(());
+    }
+    info!("Recomputing averages and percentiles for {} changed player seasons", players.len());
+
+    let affected = |pid: i32, year: i32, team: &str| players.contains(&(pid, year, team.to_string()));
+
+    let player_games: Vec<GameStats> = games
+        .iter()
+        .filter(|g| matches!((g.pid, g.year), (Some(pid), Some(year)) if affected(pid, year, &g.tt)))
+        .cloned()
+        .collect();
+    let season_averages = calculate_season_averages(&player_games);
+    store.write_season_averages(&season_averages).await?;
+
+    let years: HashSet<i32> = players.iter().map(|(_, year, _)| *year).collect();
+    let touched_averages: Vec<PlayerSeasonAverages> = store
+        .season_averages()
+        .await?
+        .into_iter()
+        .filter(|avg| years.contains(&avg.year))
+        .collect();
+    let season_percentiles = calculate_season_percentiles(&touched_averages);
+    info!("Re-ranking {} player seasons in {:?}", season_percentiles.len(), years);
+    store.write_season_percentiles(&season_percentiles).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::tests::load_fixtures;
+
+    #[tokio::test]
+    async fn recompute_ranks_only_the_requested_seasons() {
+        let store = load_fixtures().await;
+        let mut earlier = store.season_averages().await.unwrap().remove(0);
+        earlier.year = 2024;
+        store.write_season_averages(&[earlier]).await.unwrap();
+        let mut ranked = store.season_percentiles().await.unwrap().remove(0);
+        ranked.year = 2024;
+        ranked.pct_pts = 1.0;
+        store.write_season_percentiles(&[ranked]).await.unwrap();
+
+        recompute_season_analytics(&store, Some(&[2026])).await.unwrap();
+        let percentiles = store.season_percentiles().await.unwrap();
+        let earlier: Vec<f64> = percentiles.iter().filter(|p| p.year == 2024).map(|p| p.pct_pts).collect();
+        assert_eq!(earlier, [1.0]);
+        assert_eq!(percentiles.iter().filter(|p| p.year == 2026).count(), 3);
+    }
+}
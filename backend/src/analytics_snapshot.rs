@@ -0,0 +1,77 @@
+// src/analytics_snapshot.rs
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+use log::info;
+
+use crate::analytics_types::{PlayerSeasonAverages, PlayerSeasonPercentiles};
+use crate::stats_store::StatsStore;
+
+/// Every player season average and percentile, read from the stats store in one go and served
+/// from memory, keyed by (team, year).
+#[derive(Debug, Default)]
+pub struct AnalyticsSnapshot {
+    averages: HashMap<(String, i32), Vec<PlayerSeasonAverages>>,
+    percentiles: HashMap<(String, i32), Vec<PlayerSeasonPercentiles>>,
+    /// When the snapshot was read (RFC 3339).
+    pub loaded_at: String,
+}
+
+impl AnalyticsSnapshot {
+    pub async fn load(store: &dyn StatsStore) -> Result<Self, Box<dyn Error>> {
+        let mut snapshot = AnalyticsSnapshot {
+            loaded_at: chrono::Utc::now().to_rfc3339(),
+            ..Default::default()
+        };
+        for avg in store.season_averages().await? {
+            snapshot.averages.entry((avg.team.clone(), avg.year)).or_default().push(avg);
+        }
+        for pct in store.season_percentiles().await? {
+            snapshot.percentiles.entry((pct.team.clone(), pct.year)).or_default().push(pct);
+        }
+        info!(
+            "Loaded analytics snapshot: {} team seasons of averages, {} of percentiles",
+            snapshot.averages.len(),
+            snapshot.percentiles.len()
+        );
+        Ok(snapshot)
+    }
+
+    pub fn averages(&self, team: &str, year: i32) -> &[PlayerSeasonAverages] {
+        self.averages.get(&(team.to_string(), year)).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn percentiles(&self, team: &str, year: i32) -> &[PlayerSeasonPercentiles] {
+        self.percentiles.get(&(team.to_string(), year)).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// The snapshot the API currently serves. A refresh builds a complete new snapshot and
+/// swaps it in with `replace`; requests hold on to whichever snapshot they started with.
+///
+/// Refreshes and reloads take `begin_update` first, so a reload waits for a refresh that
+/// is still rewriting the analytics tables instead of reading them half-written.
+pub struct SharedAnalytics {
+    current: RwLock<Arc<AnalyticsSnapshot>>,
+    updating: tokio::sync::Mutex<()>,
+}
+
+impl SharedAnalytics {
+    pub fn new(snapshot: AnalyticsSnapshot) -> Self {
+        SharedAnalytics { current: RwLock::new(Arc::new(snapshot)), updating: tokio::sync::Mutex::new(()) }
+    }
+
+    pub fn current(&self) -> Arc<AnalyticsSnapshot> {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn replace(&self, snapshot: AnalyticsSnapshot) {
+        *self.current.write().unwrap() = Arc::new(snapshot);
+    }
+
+    /// Waits for any other refresh or reload to finish; held until the stored analytics
+    /// are consistent again and the new snapshot is swapped in.
+    pub async fn begin_update(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.updating.lock().await
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use scylla::{FromRow, SerializeRow};
 
+use crate::get_team_stats::TeamStats;
+use crate::get_four_factors::TeamFourFactors;
+use crate::get_team_shooting::TeamShooting;
+
 /// Represents a player's average statistics over an entire season.
 /// All statistical fields are `f64` as they are averages.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
@@ -222,6 +226,7 @@ pub struct PlayerSeasonPercentiles {
 }
 
 /// Type alias for PlayerRollingPercentiles, as it will have the same structure as season percentiles.
+#[allow(dead_code)]
 pub type PlayerRollingPercentiles = PlayerSeasonPercentiles;
 
 /// Combined structure that includes both averages and percentiles for a player.
@@ -324,3 +329,17 @@ pub struct PlayerStatsWithPercentiles {
     pub pct_win1: f64,
     pub pct_win2: f64,
 }
+
+/// A team's season ratings joined with its per-feed profiles, as served by `/api/team-stats`.
+/// `TeamStats` is flattened so existing clients keep reading the same top-level fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamProfile {
+    #[serde(flatten)]
+    pub stats: TeamStats,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub four_factors: Option<TeamFourFactors>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shooting: Option<TeamShooting>,
+}
@@ -0,0 +1,102 @@
+// src/batch_writer.rs
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use log::info;
+use scylla::Session;
+use scylla::batch::{Batch, BatchStatement, BatchType};
+use scylla::prepared_statement::PreparedStatement;
+use scylla::serialize::row::SerializeRow;
+use scylla::transport::errors::QueryError;
+
+/// Most rows sent in one batch. Rows are 50-60 columns wide, so this keeps batches well
+/// under Scylla's batch size warning threshold.
+const MAX_BATCH_ROWS: usize = 100;
+/// Most batches (or single-row writes) in flight at once.
+const MAX_CONCURRENT_WRITES: usize = 32;
+
+/// How a `write_batched` call went.
+#[derive(Debug, Clone)]
+pub struct WriteReport {
+    pub table: String,
+    pub rows: usize,
+    pub partitions: usize,
+    pub requests: usize,
+    pub elapsed: Duration,
+}
+
+impl WriteReport {
+    pub fn rows_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 { self.rows as f64 / secs } else { self.rows as f64 }
+    }
+
+    pub fn log(&self) {
+        info!(
+            "Wrote {} rows to {} ({} partitions, {} requests) in {:.2?}: {:.0} rows/s",
+            self.rows, self.table, self.partitions, self.requests, self.elapsed, self.rows_per_sec()
+        );
+    }
+}
+
+/// Writes `rows` with the INSERT `query`, grouping rows that share a partition key into
+/// unlogged batches and keeping up to `MAX_CONCURRENT_WRITES` requests in flight.
+///
+/// `partition_key` must return the table's partition key columns: an unlogged batch is
+/// only cheap when every row in it lands on the same partition. Rows alone in their
+/// partition are sent as plain prepared statements.
+pub async fn write_batched<T, K>(
+    session: &Session,
+    table: &str,
+    query: &str,
+    rows: &[T],
+    partition_key: impl Fn(&T) -> K,
+) -> Result<WriteReport, QueryError>
+where
+    T: SerializeRow + Sync,
+    K: Hash + Eq,
+{
+    let started = Instant::now();
+    let prepared = session.prepare(query).await?;
+
+    let mut partitions: HashMap<K, Vec<&T>> = HashMap::new();
+    for row in rows {
+        partitions.entry(partition_key(row)).or_default().push(row);
+    }
+    let partition_count = partitions.len();
+
+    // Built in a plain loop rather than `.map()` on the stream: a closure here trips up
+    // the compiler's `Send` check when this runs inside a spawned task.
+    let mut writes = Vec::new();
+    for chunk in partitions.values().flat_map(|rows| rows.chunks(MAX_BATCH_ROWS)) {
+        writes.push(write_chunk(session, &prepared, chunk));
+    }
+    let requests = writes.len();
+
+    stream::iter(writes)
+        .buffer_unordered(MAX_CONCURRENT_WRITES)
+        .try_collect::<()>()
+        .await?;
+
+    let report = WriteReport {
+        table: table.to_string(),
+        rows: rows.len(),
+        partitions: partition_count,
+        requests,
+        elapsed: started.elapsed(),
+    };
+    report.log();
+    Ok(report)
+}
+
+async fn write_chunk<T: SerializeRow>(session: &Session, prepared: &PreparedStatement, chunk: &[&T]) -> Result<(), QueryError> {
+    if let [row] = chunk {
+        session.execute(prepared, row).await?;
+        return Ok(());
+    }
+    let statements = vec![BatchStatement::PreparedStatement(prepared.clone()); chunk.len()];
+    let batch = Batch::new_with_statements(BatchType::Unlogged, statements);
+    session.batch(&batch, chunk).await?;
+    Ok(())
+}
@@ -0,0 +1,483 @@
+// src/cli.rs
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::db_config::{DbConfig, DbConfigFile, Replication, TlsConfig, parse_keyspace};
+use crate::feed_source::FeedResolver;
+use crate::http_client::FetchConfig;
+use crate::ingest::IngestOptions;
+use crate::scheduler::Schedule;
+
+/// College basketball analytics: Barttorvik ingestion, analytics and the HTTP API.
+#[derive(Debug, Parser)]
+#[command(name = "mater", version)]
+pub struct Cli {
+    /// Where feeds, analytics and ingest runs are stored
+    #[arg(long, global = true, env = "MATER_STORE", value_enum, default_value_t = StoreKind::Scylla)]
+    pub store: StoreKind,
+
+    /// Database file for `--store sqlite`, created if missing
+    #[arg(long, global = true, env = "MATER_SQLITE_PATH", value_name = "FILE", default_value = "mater.db")]
+    pub sqlite_path: PathBuf,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Backend for the stats store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StoreKind {
+    /// ScyllaDB, as set by `--db-hosts`, `--db-keyspace` and the other database options
+    Scylla,
+    /// A single SQLite file (requires building with `--features sqlite`)
+    Sqlite,
+    /// In process memory: nothing is kept once the process exits
+    Memory,
+}
+
+/// ScyllaDB connection settings. Each one falls back to the `--db-config` file, then to a
+/// local single node at 127.0.0.1:9042 with keyspace `stats`.
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Database")]
+pub struct DbArgs {
+    /// TOML file with any of: hosts, keyspace, username, password, replication, [tls]
+    #[arg(long, global = true, env = "MATER_DB_CONFIG", value_name = "FILE")]
+    pub db_config: Option<PathBuf>,
+
+    /// Contact points, `host:port`, comma separated
+    #[arg(long, global = true, env = "MATER_DB_HOSTS", value_name = "HOSTS", value_delimiter = ',')]
+    pub db_hosts: Vec<String>,
+
+    /// Keyspace holding mater's tables
+    #[arg(long, global = true, env = "MATER_DB_KEYSPACE", value_name = "NAME", value_parser = parse_keyspace)]
+    pub db_keyspace: Option<String>,
+
+    /// Username for password authentication
+    #[arg(long, global = true, env = "MATER_DB_USERNAME", value_name = "USER")]
+    pub db_username: Option<String>,
+
+    /// Password for password authentication
+    #[arg(long, global = true, env = "MATER_DB_PASSWORD", value_name = "PASSWORD", hide_env_values = true)]
+    pub db_password: Option<String>,
+
+    /// Connect over TLS, checking the server against the system trust store unless `--db-tls-ca` is given
+    #[arg(long, global = true, env = "MATER_DB_TLS")]
+    pub db_tls: bool,
+
+    /// CA certificate (PEM) to verify the servers with; implies `--db-tls`
+    #[arg(long, global = true, env = "MATER_DB_TLS_CA", value_name = "FILE")]
+    pub db_tls_ca: Option<PathBuf>,
+
+    /// Client certificate (PEM) for mutual TLS; implies `--db-tls`
+    #[arg(long, global = true, env = "MATER_DB_TLS_CERT", value_name = "FILE", requires = "db_tls_key")]
+    pub db_tls_cert: Option<PathBuf>,
+
+    /// Client private key (PEM) for mutual TLS
+    #[arg(long, global = true, env = "MATER_DB_TLS_KEY", value_name = "FILE", requires = "db_tls_cert")]
+    pub db_tls_key: Option<PathBuf>,
+
+    /// Replication for a newly created keyspace: `3` (SimpleStrategy) or `dc1=3,dc2=3` (NetworkTopologyStrategy)
+    #[arg(long, global = true, env = "MATER_DB_REPLICATION", value_name = "SPEC")]
+    pub db_replication: Option<Replication>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Fetch Barttorvik feeds and write them to the store
+    Ingest(IngestArgs),
+    /// Recompute player season averages and percentiles from stored games, and rebuild
+    /// transfers and conference tables
+    Compute(ComputeArgs),
+    /// Run the HTTP API server
+    Serve(ServeArgs),
+    /// Apply pending ScyllaDB schema migrations
+    Migrate(MigrateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct IngestArgs {
+    /// Seasons to load, e.g. `2026`, `2025,2026` or `2008-2026`
+    #[arg(long, env = "MATER_SEASONS", default_value_t = Seasons(vec![current_season()]))]
+    pub season: Seasons,
+
+    /// Feeds to load (defaults to all of them)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub source: Vec<Source>,
+
+    /// Parse and write feeds even if they are unchanged since the last load
+    #[arg(long)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub feed: FeedArgs,
+
+    /// Recompute averages and percentiles for every player once ingestion finishes
+    /// (loading games already refreshes the players whose games changed)
+    #[arg(long)]
+    pub compute: bool,
+}
+
+/// Where feeds are read from and how they are fetched, archived and checked; shared by
+/// `mater ingest` and the scheduled refreshes of `mater serve`.
+#[derive(Debug, Args)]
+pub struct FeedArgs {
+    /// Read every feed from an archived snapshot directory instead of Barttorvik.
+    /// Files use their published names (`2026_all_advgames.json.gz`, `2026_getadvstats.csv`, ...)
+    /// and may be stored plain or gzipped.
+    #[arg(long, value_name = "DIR")]
+    pub from_dir: Option<PathBuf>,
+
+    /// Read one feed from a local path (plain or `.gz`) or URL, e.g.
+    /// `games=/data/{year}_all_advgames.json.gz`. `{year}` is replaced with each season.
+    #[arg(long = "input", value_name = "SOURCE=PATH", value_parser = parse_input)]
+    pub inputs: Vec<(Source, String)>,
+
+    /// Directory raw feeds are archived to, one dated subdirectory per day with a SHA-256 manifest
+    #[arg(long, env = "MATER_ARCHIVE_DIR", value_name = "DIR", default_value = "archive")]
+    pub archive_dir: PathBuf,
+
+    /// Don't archive raw feeds (this also disables skipping unchanged feeds)
+    #[arg(long)]
+    pub no_archive: bool,
+
+    /// Seconds allowed for each HTTP request, including the download
+    #[arg(long, value_name = "SECS", default_value_t = 120)]
+    pub http_timeout: u64,
+
+    /// Retries (with exponential backoff) for timeouts, connection errors, 429s and 5xxs
+    #[arg(long, value_name = "N", default_value_t = 4)]
+    pub http_retries: u32,
+
+    /// Minimum milliseconds between requests, to stay polite to Barttorvik during backfills
+    #[arg(long, value_name = "MS", default_value_t = 1000)]
+    pub request_interval: u64,
+
+    /// Directory for cached downloads, revalidated with ETag/Last-Modified instead of re-downloaded
+    #[arg(long, env = "MATER_CACHE_DIR", value_name = "DIR", default_value = "cache")]
+    pub cache_dir: PathBuf,
+
+    /// Always download feeds in full instead of sending conditional requests
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Refuse to write a feed when more than this fraction of its rows don't match the
+    /// expected column layout (wrong width or wrong value types), from 0 to 1
+    #[arg(long, value_name = "RATIO", default_value_t = 0.05, value_parser = parse_ratio)]
+    pub max_drift: f64,
+}
+
+#[derive(Debug, Args)]
+pub struct ComputeArgs {
+    /// Only recompute these seasons (defaults to every stored season)
+    #[arg(long)]
+    pub season: Option<Seasons>,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    /// List applied and pending migrations without applying anything
+    #[arg(long)]
+    pub status: bool,
+    /// Apply migrations that drop tables still holding rows (the rows must be reloaded after)
+    #[arg(long)]
+    pub allow_data_loss: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address to bind the API server to
+    #[arg(long, default_value = "0.0.0.0")]
+    pub bind: String,
+
+    /// Port to bind the API server to
+    #[arg(long, default_value_t = 8000)]
+    pub port: u16,
+
+    /// Re-run ingestion and analytics in the background on this cron schedule
+    /// (`minute hour day-of-month month day-of-week`, UTC), e.g. `0 */3 * 11-12,1-4 *`
+    #[arg(long, env = "MATER_REFRESH_SCHEDULE", value_name = "CRON")]
+    pub refresh_schedule: Option<Schedule>,
+
+    /// Seasons each scheduled refresh loads (defaults to the season being played at the time)
+    #[arg(long, value_name = "SEASONS")]
+    pub refresh_season: Option<Seasons>,
+
+    /// Feeds each scheduled refresh loads (defaults to all of them)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub refresh_source: Vec<Source>,
+
+    /// Re-read averages and percentiles from the store this often, so a separate
+    /// `mater ingest` or `mater compute` shows up without a restart (0 disables)
+    #[arg(long, env = "MATER_RELOAD_INTERVAL", value_name = "MINUTES", default_value_t = 5)]
+    pub reload_interval: u64,
+
+    #[command(flatten)]
+    pub feed: FeedArgs,
+}
+
+/// A list of seasons given on the command line as `2026`, `2024,2026` or `2008-2026`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seasons(pub Vec<i32>);
+
+impl std::str::FromStr for Seasons {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        parse_seasons(spec).map(Seasons)
+    }
+}
+
+impl std::fmt::Display for Seasons {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seasons: Vec<String> = self.0.iter().map(|s| s.to_string()).collect();
+        write!(f, "{}", seasons.join(","))
+    }
+}
+
+/// A Barttorvik feed that `mater ingest` can load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Source {
+    /// Player season stats (getadvstats.php)
+    Players,
+    /// Team ratings (team_results.json)
+    Teams,
+    /// Player game lines (all_advgames.json.gz)
+    Games,
+    /// Full schedule with predictions (super_sked.csv)
+    Schedule,
+    /// Team four factors (fffinal.csv)
+    FourFactors,
+    /// Player shot-distance profiles (pbp_playerstat_array.json)
+    PlayerShots,
+    /// Team shooting splits (pbp_teamsstats.json)
+    TeamShooting,
+    /// Team game results (results.csv)
+    TeamResults,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.to_possible_value().expect("no skipped variants");
+        write!(f, "{}", value.get_name())
+    }
+}
+
+impl DbArgs {
+    /// The connection settings: command line and environment first, then the config file,
+    /// then the defaults.
+    pub fn config(&self) -> Result<DbConfig, Box<dyn Error>> {
+        let file = match &self.db_config {
+            Some(path) => DbConfigFile::load(path)?,
+            None => DbConfigFile::default(),
+        };
+        let defaults = DbConfig::default();
+
+        let username = self.db_username.clone().or(file.username);
+        let password = self.db_password.clone().or(file.password);
+        let credentials = match (username, password) {
+            (Some(username), Some(password)) => Some((username, password)),
+            (None, None) => None,
+            _ => return Err("Database username and password must be given together".into()),
+        };
+
+        let mut tls = file.tls;
+        if self.db_tls || self.db_tls_ca.is_some() || self.db_tls_cert.is_some() {
+            let tls = tls.get_or_insert_with(TlsConfig::default);
+            if let Some(ca) = &self.db_tls_ca {
+                tls.ca_cert = Some(ca.clone());
+            }
+            if let (Some(cert), Some(key)) = (&self.db_tls_cert, &self.db_tls_key) {
+                tls.client_cert = Some(cert.clone());
+                tls.client_key = Some(key.clone());
+            }
+        }
+
+        Ok(DbConfig {
+            hosts: if self.db_hosts.is_empty() { file.hosts.unwrap_or(defaults.hosts) } else { self.db_hosts.clone() },
+            keyspace: self.db_keyspace.clone().or(file.keyspace).unwrap_or(defaults.keyspace),
+            credentials,
+            tls,
+            replication: self.db_replication.clone().or(file.replication).unwrap_or(defaults.replication),
+        })
+    }
+}
+
+impl IngestArgs {
+    pub fn options(&self) -> IngestOptions {
+        self.feed.options(self.season.0.clone(), sources_or_all(&self.source), self.force)
+    }
+}
+
+impl ServeArgs {
+    /// What a scheduled refresh loads: the configured seasons, or the season being played
+    /// when the refresh starts, so a long-running server rolls over to a new season.
+    pub fn refresh_options(&self) -> IngestOptions {
+        let seasons = match &self.refresh_season {
+            Some(seasons) => seasons.0.clone(),
+            None => vec![current_season()],
+        };
+        self.feed.options(seasons, sources_or_all(&self.refresh_source), false)
+    }
+}
+
+impl FeedArgs {
+    pub fn options(&self, seasons: Vec<i32>, sources: Vec<Source>, force: bool) -> IngestOptions {
+        IngestOptions {
+            seasons,
+            sources,
+            feeds: self.feeds(),
+            archive_dir: (!self.no_archive).then(|| self.archive_dir.clone()),
+            force,
+            max_drift: self.max_drift,
+            fetch: FetchConfig {
+                timeout: Duration::from_secs(self.http_timeout),
+                max_retries: self.http_retries,
+                base_backoff: Duration::from_secs(2),
+                min_interval: Duration::from_millis(self.request_interval),
+                cache_dir: (!self.no_cache).then(|| self.cache_dir.clone()),
+            },
+        }
+    }
+
+    /// Where each feed should be read from for this run.
+    fn feeds(&self) -> FeedResolver {
+        let inputs: HashMap<Source, String> = self.inputs.iter().cloned().collect();
+        FeedResolver::new(self.from_dir.clone(), inputs)
+    }
+}
+
+/// The requested feeds, or every feed when none were given.
+fn sources_or_all(sources: &[Source]) -> Vec<Source> {
+    if sources.is_empty() {
+        Source::value_variants().to_vec()
+    } else {
+        sources.to_vec()
+    }
+}
+
+// Parses an `--input` value of the form `source=path-or-url`.
+fn parse_input(value: &str) -> Result<(Source, String), String> {
+    let (source, location) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected SOURCE=PATH, got '{}'", value))?;
+    let source = Source::from_str(source.trim(), true)?;
+    if location.trim().is_empty() {
+        return Err(format!("No path given in '{}'", value));
+    }
+    Ok((source, location.trim().to_string()))
+}
+
+// Parses a `--max-drift` value, a fraction of rows from 0 to 1.
+fn parse_ratio(value: &str) -> Result<f64, String> {
+    let ratio: f64 = value.trim().parse().map_err(|_| format!("Invalid ratio '{}'", value))?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(format!("Ratio '{}' is not between 0 and 1", value));
+    }
+    Ok(ratio)
+}
+
+/// Returns the season currently being played. Barttorvik labels a season by the
+/// calendar year it ends in, so games from October onwards belong to next year's season.
+pub fn current_season() -> i32 {
+    season_on(Utc::now())
+}
+
+/// The season being played on `day`.
+pub fn season_on(day: DateTime<Utc>) -> i32 {
+    if day.month() >= 10 { day.year() + 1 } else { day.year() }
+}
+
+// The first season Barttorvik publishes feeds for.
+const FIRST_SEASON: i32 = 2008;
+
+/// Parses a season list such as `2026`, `2024,2026` or `2008-2026`. Every season must lie
+/// between 2008 and the one after the current season.
+pub fn parse_seasons(spec: &str) -> Result<Vec<i32>, String> {
+    let latest = current_season() + 1;
+    let season = |value: &str| -> Result<i32, String> {
+        let year: i32 = value.trim().parse().map_err(|_| format!("Invalid season '{}'", value))?;
+        if !(FIRST_SEASON..=latest).contains(&year) {
+            return Err(format!("Season {} is outside {}-{}", year, FIRST_SEASON, latest));
+        }
+        Ok(year)
+    };
+
+    let mut seasons = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (season(start)?, season(end)?);
+                if start > end {
+                    return Err(format!("Invalid season range '{}'", part));
+                }
+                seasons.extend(start..=end);
+            }
+            None => seasons.push(season(part)?),
+        }
+    }
+    seasons.sort_unstable();
+    seasons.dedup();
+    if seasons.is_empty() {
+        return Err(format!("No seasons in '{}'", spec));
+    }
+    Ok(seasons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_season_lists_and_ranges() {
+        assert_eq!(parse_seasons("2026"), Ok(vec![2026]));
+        assert_eq!(parse_seasons(" 2026 , 2024,2026,"), Ok(vec![2024, 2026]));
+        assert_eq!(parse_seasons("2010-2012,2008"), Ok(vec![2008, 2010, 2011, 2012]));
+        assert_eq!(parse_seasons("2012-2012"), Ok(vec![2012]));
+        let latest = current_season() + 1;
+        assert_eq!(parse_seasons(&format!("{}-{}", FIRST_SEASON, latest)).unwrap().len() as i32, latest - FIRST_SEASON + 1);
+
+        assert!(parse_seasons("2026-2024").unwrap_err().contains("Invalid season range"));
+        assert!(parse_seasons("").unwrap_err().contains("No seasons"));
+        assert!(parse_seasons(" , ").unwrap_err().contains("No seasons"));
+        for spec in ["twenty", "2024-", "-2026", "2024-2025-2026", "2026.5"] {
+            assert!(parse_seasons(spec).is_err(), "'{}' was accepted", spec);
+        }
+    }
+
+    #[test]
+    fn seasons_are_bounded() {
+        let latest = current_season() + 1;
+        for spec in ["0-2000000000", "2007", "1990-2010", &(latest + 1).to_string(), &format!("2020-{}", latest + 1)] {
+            assert!(parse_seasons(spec).unwrap_err().contains("is outside"), "'{}' was accepted", spec);
+        }
+    }
+
+    #[test]
+    fn parses_feed_inputs() {
+        assert_eq!(parse_input("players=/data/players.csv"), Ok((Source::Players, "/data/players.csv".to_string())));
+        assert_eq!(
+            parse_input(" Four-Factors = https://example.com/{year}_fffinal.csv "),
+            Ok((Source::FourFactors, "https://example.com/{year}_fffinal.csv".to_string()))
+        );
+        for value in ["players", "players=", "players= ", "roster=/data/roster.csv", "=/data/players.csv"] {
+            assert!(parse_input(value).is_err(), "'{}' was accepted", value);
+        }
+    }
+
+    #[test]
+    fn ratios_lie_between_zero_and_one() {
+        assert_eq!(parse_ratio("0"), Ok(0.0));
+        assert_eq!(parse_ratio(" 0.05 "), Ok(0.05));
+        assert_eq!(parse_ratio("1"), Ok(1.0));
+        for value in ["-0.1", "1.01", "NaN", "5%", ""] {
+            assert!(parse_ratio(value).is_err(), "'{}' was accepted", value);
+        }
+    }
+}
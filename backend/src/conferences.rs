@@ -0,0 +1,268 @@
+// src/conferences.rs
+use std::collections::{BTreeMap, BTreeSet};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::get_team_stats::TeamStats;
+use crate::stats_store::{StatsStore, StoreResult};
+
+/// One conference's season, aggregated from its members' `team_stats` rows
+/// (`conference_stats`).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct ConferenceSummary {
+    pub year: i32,
+    pub conf: String,
+    pub teams: i32,
+    /// Rank among the season's conferences by `avg_barthag`.
+    pub rank: i32,
+    pub avg_adjoe: f64,
+    pub avg_adjde: f64,
+    pub avg_adj_tempo: f64,
+    pub avg_barthag: f64,
+    pub min_barthag: f64,
+    pub p25_barthag: f64,
+    pub median_barthag: f64,
+    pub p75_barthag: f64,
+    pub max_barthag: f64,
+    /// Average of the members' non-conference strength of schedule.
+    pub avg_nconf_sos: f64,
+    /// Rank among the season's conferences by `avg_nconf_sos`, toughest first.
+    pub nconf_sos_rank: i32,
+    /// The team leading the standings.
+    pub leader: String,
+}
+
+/// A team's place in its conference standings (`conference_standings`).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct ConferenceStanding {
+    pub year: i32,
+    pub conf: String,
+    /// 1 for the leader: by conference win %, then barthag.
+    pub conf_rank: i32,
+    pub team: String,
+    pub conf_record: String,
+    pub conf_win_perc: f64,
+    pub record: String,
+    pub barthag: f64,
+    pub adjoe: f64,
+    pub adjde: f64,
+    /// Adjusted efficiencies in conference games only.
+    pub conf_adj_o: f64,
+    pub conf_adj_d: f64,
+    pub nconf_sos: f64,
+}
+
+/// A conference with its standings, as served by `/api/conferences/{conf}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConferenceTable {
+    #[serde(flatten)]
+    pub summary: ConferenceSummary,
+    pub standings: Vec<ConferenceStanding>,
+}
+
+// Linear interpolation between the closest ranks of an ascending, non-empty slice.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0), |(sum, n), v| (sum + v, n + 1));
+    if n == 0 { 0.0 } else { sum / n as f64 }
+}
+
+// Ranks `summaries` by `key`, highest first, writing the rank with `set`.
+fn rank_by(summaries: &mut [ConferenceSummary], key: fn(&ConferenceSummary) -> f64, set: fn(&mut ConferenceSummary, i32)) {
+    let mut order: Vec<usize> = (0..summaries.len()).collect();
+    order.sort_by(|&a, &b| key(&summaries[b]).total_cmp(&key(&summaries[a])));
+    for (rank, i) in order.into_iter().enumerate() {
+        set(&mut summaries[i], rank as i32 + 1);
+    }
+}
+
+/// Builds `year`'s conference summaries and standings from its `team_stats` rows.
+/// Teams without a conference are left out.
+pub fn conference_tables(year: i32, teams: &[TeamStats]) -> (Vec<ConferenceSummary>, Vec<ConferenceStanding>) {
+    let mut by_conf: BTreeMap<&str, Vec<&TeamStats>> = BTreeMap::new();
+    for team in teams.iter().filter(|t| !t.conf.trim().is_empty()) {
+        by_conf.entry(team.conf.as_str()).or_default().push(team);
+    }
+
+    let mut summaries = Vec::with_capacity(by_conf.len());
+    let mut standings = Vec::with_capacity(teams.len());
+    for (conf, mut members) in by_conf {
+        members.sort_by(|a, b| {
+            b.conf_win_perc
+                .total_cmp(&a.conf_win_perc)
+                .then_with(|| b.barthag.total_cmp(&a.barthag))
+        });
+        let mut barthags: Vec<f64> = members.iter().map(|t| t.barthag).collect();
+        barthags.sort_by(f64::total_cmp);
+
+        summaries.push(ConferenceSummary {
+            year,
+            conf: conf.to_string(),
+            teams: members.len() as i32,
+            rank: 0,
+            avg_adjoe: mean(members.iter().map(|t| t.adjoe)),
+            avg_adjde: mean(members.iter().map(|t| t.adjde)),
+            avg_adj_tempo: mean(members.iter().map(|t| t.adj_tempo)),
+            avg_barthag: mean(barthags.iter().copied()),
+            min_barthag: barthags[0],
+            p25_barthag: quantile(&barthags, 0.25),
+            median_barthag: quantile(&barthags, 0.5),
+            p75_barthag: quantile(&barthags, 0.75),
+            max_barthag: barthags[barthags.len() - 1],
+            avg_nconf_sos: mean(members.iter().map(|t| t.nconf_sos)),
+            nconf_sos_rank: 0,
+            leader: members[0].team.clone(),
+        });
+        standings.extend(members.iter().enumerate().map(|(i, t)| ConferenceStanding {
+            year,
+            conf: conf.to_string(),
+            conf_rank: i as i32 + 1,
+            team: t.team.clone(),
+            conf_record: t.conf_record.clone(),
+            conf_win_perc: t.conf_win_perc,
+            record: t.record.clone(),
+            barthag: t.barthag,
+            adjoe: t.adjoe,
+            adjde: t.adjde,
+            conf_adj_o: t.conf_adj_o,
+            conf_adj_d: t.conf_adj_d,
+            nconf_sos: t.nconf_sos,
+        }));
+    }
+
+    rank_by(&mut summaries, |c| c.avg_barthag, |c, rank| c.rank = rank);
+    rank_by(&mut summaries, |c| c.avg_nconf_sos, |c, rank| c.nconf_sos_rank = rank);
+    (summaries, standings)
+}
+
+/// Rebuilds `conference_stats` and `conference_standings` for `seasons` (every season with
+/// a conference in the team registry when `None`) from the stored `team_stats` rows,
+/// replacing whatever each season held before.
+pub async fn refresh_conferences(store: &dyn StatsStore, seasons: Option<&[i32]>) -> StoreResult<()> {
+    let seasons: BTreeSet<i32> = match seasons {
+        Some(seasons) => seasons.iter().copied().collect(),
+        None => store.teams().await?.iter().flat_map(|t| t.conferences.keys().copied()).collect(),
+    };
+    for year in seasons {
+        let (summaries, standings) = conference_tables(year, &store.team_stats(year).await?);
+        info!("Built {} conference tables for {}", summaries.len(), year);
+        store.clear_conferences(year).await?;
+        if !summaries.is_empty() {
+            store.write_conferences(&summaries).await?;
+            store.write_conference_standings(&standings).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn insert_conferences(session: &Session, summaries: &[ConferenceSummary]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO conference_stats (
+        year, conf, teams, rank, avg_adjoe, avg_adjde, avg_adj_tempo, avg_barthag, min_barthag,
+        p25_barthag, median_barthag, p75_barthag, max_barthag, avg_nconf_sos, nconf_sos_rank, leader
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+    write_batched(session, "conference_stats", query, summaries, |c| c.year).await
+}
+
+pub async fn insert_conference_standings(session: &Session, standings: &[ConferenceStanding]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO conference_standings (
+        year, conf, conf_rank, team, conf_record, conf_win_perc, record, barthag, adjoe, adjde,
+        conf_adj_o, conf_adj_d, nconf_sos
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+    write_batched(session, "conference_standings", query, standings, |s| (s.year, s.conf.clone())).await
+}
+
+/// Deletes a season's `conference_stats` partition and the `conference_standings`
+/// partition of every conference it lists.
+pub async fn delete_conferences(session: &Session, year: i32) -> Result<(), QueryError> {
+    let delete_standings = session.prepare("DELETE FROM conference_standings WHERE year = ? AND conf = ?").await?;
+    for summary in get_conferences_from_db(session, year).await? {
+        session.execute(&delete_standings, (year, &summary.conf)).await?;
+    }
+    let delete_summaries = session.prepare("DELETE FROM conference_stats WHERE year = ?").await?;
+    session.execute(&delete_summaries, (year,)).await?;
+    Ok(())
+}
+
+/// Fetches every conference's summary for a season (one partition).
+pub async fn get_conferences_from_db(session: &Session, year: i32) -> Result<Vec<ConferenceSummary>, QueryError> {
+    let query = r#"
+    SELECT year, conf, teams, rank, avg_adjoe, avg_adjde, avg_adj_tempo, avg_barthag, min_barthag,
+           p25_barthag, median_barthag, p75_barthag, max_barthag, avg_nconf_sos, nconf_sos_rank, leader
+    FROM conference_stats WHERE year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (year,)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut summaries = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match ConferenceSummary::from_row(row) {
+            Ok(c) => summaries.push(c),
+            Err(e) => error!("Conference row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(summaries)
+}
+
+/// Fetches a conference's standings for a season (one partition), leader first.
+pub async fn get_conference_standings_from_db(session: &Session, conf: &str, year: i32) -> Result<Vec<ConferenceStanding>, QueryError> {
+    let query = r#"
+    SELECT year, conf, conf_rank, team, conf_record, conf_win_perc, record, barthag, adjoe, adjde,
+           conf_adj_o, conf_adj_d, nconf_sos
+    FROM conference_standings WHERE year = ? AND conf = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (year, conf)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut standings = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match ConferenceStanding::from_row(row) {
+            Ok(s) => standings.push(s),
+            Err(e) => error!("Conference standings row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(standings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::tests::load_fixtures;
+
+    #[tokio::test]
+    async fn refresh_replaces_the_season() {
+        let store = load_fixtures().await;
+        // Kansas moves to the ACC: the B12 loses a member and its old standings row.
+        let mut teams = store.team_stats(2026).await.unwrap();
+        teams.iter_mut().filter(|t| t.team == "Kansas").for_each(|t| t.conf = "ACC".to_string());
+        store.write_team_stats(&teams).await.unwrap();
+
+        refresh_conferences(&store, Some(&[2026])).await.unwrap();
+        let b12 = store.conference_standings("B12", 2026).await.unwrap();
+        let ranks: Vec<(i32, &str)> = b12.iter().map(|s| (s.conf_rank, s.team.as_str())).collect();
+        assert_eq!(ranks, [(1, "Iowa St."), (2, "Texas Tech")]);
+        assert_eq!(store.conference_standings("ACC", 2026).await.unwrap().len(), 3);
+
+        // A season with no conferences left ends up empty rather than keeping its old rows.
+        teams.iter_mut().for_each(|t| t.conf = String::new());
+        store.write_team_stats(&teams).await.unwrap();
+        refresh_conferences(&store, Some(&[2026])).await.unwrap();
+        assert!(store.conferences(2026).await.unwrap().is_empty());
+        assert!(store.conference_standings("ACC", 2026).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+// src/db_config.rs
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use openssl::ssl::{SslContext, SslContextBuilder, SslFiletype, SslMethod, SslVerifyMode};
+use serde::{Deserialize, Deserializer};
+
+/// How to reach ScyllaDB and how the keyspace is laid out. Built by `DbArgs::config` from
+/// the command line and environment, falling back to a `--db-config` TOML file and then
+/// to a local single-node setup.
+#[derive(Debug, Clone)]
+pub struct DbConfig {
+    /// Contact points, `host:port`; the driver discovers the rest of the cluster from them.
+    pub hosts: Vec<String>,
+    pub keyspace: String,
+    /// Username and password, when the cluster has authentication enabled.
+    pub credentials: Option<(String, String)>,
+    pub tls: Option<TlsConfig>,
+    /// Replication used when `mater migrate` creates the keyspace.
+    pub replication: Replication,
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        DbConfig {
+            hosts: vec!["127.0.0.1:9042".to_string()],
+            keyspace: "stats".to_string(),
+            credentials: None,
+            tls: None,
+            replication: Replication::default(),
+        }
+    }
+}
+
+/// TLS for client connections. Without `ca_cert` the server certificate is checked against
+/// the system trust store; `client_cert` and `client_key` enable mutual TLS.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub ca_cert: Option<PathBuf>,
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    /// Accept any server certificate (testing only).
+    #[serde(default)]
+    pub skip_verify: bool,
+}
+
+impl TlsConfig {
+    pub fn ssl_context(&self) -> Result<SslContext, Box<dyn Error>> {
+        let mut builder = SslContextBuilder::new(SslMethod::tls())?;
+        match &self.ca_cert {
+            Some(ca) => builder
+                .set_ca_file(ca)
+                .map_err(|e| format!("Failed to load TLS CA certificate {}: {}", ca.display(), e))?,
+            None => builder.set_default_verify_paths()?,
+        }
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                builder
+                    .set_certificate_chain_file(cert)
+                    .map_err(|e| format!("Failed to load TLS client certificate {}: {}", cert.display(), e))?;
+                builder
+                    .set_private_key_file(key, SslFiletype::PEM)
+                    .map_err(|e| format!("Failed to load TLS client key {}: {}", key.display(), e))?;
+                builder.check_private_key().map_err(|e| format!("TLS client key doesn't match the certificate: {}", e))?;
+            }
+            (None, None) => {}
+            _ => return Err("TLS client_cert and client_key must be given together".into()),
+        }
+        builder.set_verify(if self.skip_verify { SslVerifyMode::NONE } else { SslVerifyMode::PEER });
+        Ok(builder.build())
+    }
+}
+
+/// Keyspace replication, written `3` for `SimpleStrategy` with that replication factor, or
+/// `dc1=3,dc2=2` for `NetworkTopologyStrategy` with a factor per datacenter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Replication {
+    Simple(u32),
+    NetworkTopology(BTreeMap<String, u32>),
+}
+
+impl Default for Replication {
+    fn default() -> Self {
+        Replication::Simple(1)
+    }
+}
+
+impl Replication {
+    /// The replication map for `CREATE KEYSPACE ... WITH replication = `.
+    pub fn to_cql(&self) -> String {
+        match self {
+            Replication::Simple(rf) => format!("{{ 'class': 'SimpleStrategy', 'replication_factor': {} }}", rf),
+            Replication::NetworkTopology(dcs) => {
+                let factors: Vec<String> = dcs.iter().map(|(dc, rf)| format!("'{}': {}", dc, rf)).collect();
+                format!("{{ 'class': 'NetworkTopologyStrategy', {} }}", factors.join(", "))
+            }
+        }
+    }
+}
+
+impl FromStr for Replication {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid replication '{}' (expected e.g. `3` or `dc1=3,dc2=2`)", spec);
+        if !spec.contains('=') {
+            return spec.trim().parse().ok().filter(|&rf| rf > 0).map(Replication::Simple).ok_or_else(invalid);
+        }
+        let mut dcs = BTreeMap::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (dc, rf) = part.split_once('=').ok_or_else(invalid)?;
+            let dc = dc.trim();
+            // Datacenter names end up inside a CQL string literal.
+            if dc.is_empty() || !dc.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                return Err(invalid());
+            }
+            let rf = rf.trim().parse().ok().filter(|&rf| rf > 0).ok_or_else(invalid)?;
+            dcs.insert(dc.to_string(), rf);
+        }
+        if dcs.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Replication::NetworkTopology(dcs))
+    }
+}
+
+impl fmt::Display for Replication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Replication::Simple(rf) => write!(f, "{}", rf),
+            Replication::NetworkTopology(dcs) => {
+                let factors: Vec<String> = dcs.iter().map(|(dc, rf)| format!("{}={}", dc, rf)).collect();
+                write!(f, "{}", factors.join(","))
+            }
+        }
+    }
+}
+
+/// Checks that a keyspace name is a plain CQL identifier, since it is spliced into
+/// `CREATE KEYSPACE` and `USE`.
+pub fn parse_keyspace(name: &str) -> Result<String, String> {
+    let valid = name.len() <= 48
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(format!("Invalid keyspace '{}' (letters, digits and underscores, starting with a letter)", name))
+    }
+}
+
+/// The `--db-config` file. Every key is optional; anything set on the command line or in
+/// the environment wins over it.
+///
+/// ```toml
+/// hosts = ["10.0.0.1:9042", "10.0.0.2:9042"]
+/// keyspace = "stats"
+/// username = "mater"
+/// password = "..."
+/// replication = "dc1=3,dc2=3"
+///
+/// [tls]
+/// ca_cert = "/etc/mater/ca.pem"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DbConfigFile {
+    pub hosts: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "keyspace_from_str")]
+    pub keyspace: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub tls: Option<TlsConfig>,
+    #[serde(default, deserialize_with = "replication_from_str")]
+    pub replication: Option<Replication>,
+}
+
+impl DbConfigFile {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(toml::from_str(&text).map_err(|e| format!("Invalid database config {}: {}", path.display(), e))?)
+    }
+}
+
+fn keyspace_from_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_keyspace(&name).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn replication_from_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Replication>, D::Error> {
+    // A bare replication factor may be written as a TOML integer.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Spec {
+        Factor(u32),
+        Text(String),
+    }
+    let spec = match Spec::deserialize(deserializer)? {
+        Spec::Factor(rf) => rf.to_string(),
+        Spec::Text(spec) => spec,
+    };
+    spec.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_replication_specs() {
+        assert_eq!("3".parse(), Ok(Replication::Simple(3)));
+        let dcs = BTreeMap::from([("dc1".to_string(), 3), ("dc2".to_string(), 2)]);
+        assert_eq!(" dc1=3, dc2 = 2 ".parse(), Ok(Replication::NetworkTopology(dcs.clone())));
+        assert_eq!(
+            Replication::NetworkTopology(dcs).to_cql(),
+            "{ 'class': 'NetworkTopologyStrategy', 'dc1': 3, 'dc2': 2 }"
+        );
+
+        for spec in ["", " ", ",", "0", "dc1=0", "-1", "three", "dc1=3,dc2", "=3", "dc 1=3", "dc1'=3", "dc1=3,'x'=1"] {
+            assert!(spec.parse::<Replication>().is_err(), "'{}' was accepted", spec);
+        }
+    }
+
+    #[test]
+    fn keyspaces_are_plain_identifiers() {
+        assert_eq!(parse_keyspace("stats_2026"), Ok("stats_2026".to_string()));
+        assert!(parse_keyspace(&"k".repeat(48)).is_ok());
+
+        for name in ["", "2026_stats", "_stats", "stats-2026", "stats;drop", "stats space", &"k".repeat(49)] {
+            assert!(parse_keyspace(name).is_err(), "'{}' was accepted", name);
+        }
+    }
+
+    #[test]
+    fn config_file_accepts_a_bare_replication_factor() {
+        let config: DbConfigFile = toml::from_str("replication = 3").unwrap();
+        assert_eq!(config.replication, Some(Replication::Simple(3)));
+        let config: DbConfigFile = toml::from_str("replication = \"dc1=3\"").unwrap();
+        assert_eq!(config.replication.unwrap().to_string(), "dc1=3");
+
+        assert!(toml::from_str::<DbConfigFile>("replication = 0").is_err());
+        assert!(toml::from_str::<DbConfigFile>("replication = \"dc1=x\"").is_err());
+        assert!(toml::from_str::<DbConfigFile>("keyspace = \"1stats\"").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+// src/feed_archive.rs
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::feed_source::FeedLocation;
+
+const MANIFEST_FILE: &str = "manifest.json";
+// Every distinct feed body, stored once under its SHA-256.
+const BLOB_DIR: &str = "blobs";
+// Hash of the last copy of each feed that was parsed and written, per database.
+const LOADED_FILE: &str = "loaded.json";
+
+/// One archived feed file in a day's `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub sha256: String,
+    /// The stored content, relative to the archive root.
+    #[serde(default)]
+    pub blob: String,
+    pub bytes: usize,
+    pub source: String,
+    pub fetched_at: String,
+}
+
+/// Keeps the raw bytes of every feed an ingest downloads, one directory per day:
+///
+/// ```text
+/// archive/
+///   loaded.json                 database -> file name -> sha256 last written to it
+///   blobs/
+///     3f9a...e1                 each distinct feed body, once
+///   2026-01-15/
+///     manifest.json             file name -> sha256, blob, size, origin, fetch time
+///     2026_all_advgames.json.gz hard link to its blob
+///     2026_getadvstats.csv
+/// ```
+///
+/// A feed that hasn't changed since an earlier day adds a manifest entry and a link, not
+/// another copy of its bytes.
+/// A day directory uses the same file names as `--from-dir`, so any day can be replayed.
+/// `loaded.json` is kept per database (`StatsStore::name`), so loading into a new keyspace
+/// or SQLite file never skips a feed that only some other database has.
+pub struct FeedArchive {
+    root: PathBuf,
+    day_dir: PathBuf,
+    manifest: BTreeMap<String, ManifestEntry>,
+    store: String,
+    loaded: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl FeedArchive {
+    /// Opens (creating if needed) the archive under `root` and today's directory in it,
+    /// for loads into the database named `store`.
+    pub fn open(root: &Path, store: &str) -> Result<Self, Box<dyn Error>> {
+        let day_dir = root.join(Utc::now().format("%Y-%m-%d").to_string());
+        fs::create_dir_all(&day_dir)?;
+
+        let archive = FeedArchive {
+            root: root.to_path_buf(),
+            manifest: read_json(&day_dir.join(MANIFEST_FILE))?,
+            loaded: read_json(&root.join(LOADED_FILE))?,
+            store: store.to_string(),
+            day_dir,
+        };
+        info!("Archiving raw feeds to {}", archive.day_dir.display());
+        Ok(archive)
+    }
+
+    /// Stores a feed's raw bytes (once per distinct content), links them into today's
+    /// directory and records them in the manifest. Returns the content's SHA-256.
+    pub fn store(&mut self, file_name: &str, location: &FeedLocation, raw: &[u8]) -> Result<String, Box<dyn Error>> {
+        let sha256 = sha256_hex(raw);
+
+        // Keep the `.gz` suffix only on content that actually is gzipped.
+        let file_name = if raw.starts_with(&[0x1f, 0x8b]) {
+            file_name
+        } else {
+            file_name.trim_end_matches(".gz")
+        };
+
+        let blob = Path::new(BLOB_DIR).join(&sha256);
+        let blob_path = self.root.join(&blob);
+        if !blob_path.exists() {
+            fs::create_dir_all(self.root.join(BLOB_DIR))?;
+            // Written aside and renamed, so an interrupted write never leaves a truncated blob.
+            let partial = blob_path.with_extension("partial");
+            fs::write(&partial, raw)?;
+            fs::rename(&partial, &blob_path)?;
+        }
+        let day_file = self.day_dir.join(file_name);
+        if day_file.exists() {
+            fs::remove_file(&day_file)?;
+        }
+        if let Err(e) = fs::hard_link(&blob_path, &day_file) {
+            warn!("Couldn't link {} to {} ({}); copying it", day_file.display(), blob_path.display(), e);
+            fs::copy(&blob_path, &day_file)?;
+        }
+
+        self.manifest.insert(
+            file_name.to_string(),
+            ManifestEntry {
+                sha256: sha256.clone(),
+                blob: blob.to_string_lossy().into_owned(),
+                bytes: raw.len(),
+                source: location.to_string(),
+                fetched_at: Utc::now().to_rfc3339(),
+            },
+        );
+        write_json(&self.day_dir.join(MANIFEST_FILE), &self.manifest)?;
+
+        Ok(sha256)
+    }
+
+    /// Whether this exact content was already parsed and written to this database.
+    pub fn is_loaded(&self, file_name: &str, sha256: &str) -> bool {
+        self.loaded.get(&self.store).and_then(|files| files.get(file_name)).is_some_and(|h| h == sha256)
+    }
+
+    /// Records that this content was written to the database, so an unchanged copy is
+    /// skipped next time. Only call this once the insert succeeded.
+    pub fn mark_loaded(&mut self, file_name: &str, sha256: &str) -> Result<(), Box<dyn Error>> {
+        self.loaded.entry(self.store.clone()).or_default().insert(file_name.to_string(), sha256.to_string());
+        write_json(&self.root.join(LOADED_FILE), &self.loaded)
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn read_json<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> Result<T, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let data = fs::read(path)?;
+    serde_json::from_slice(&data).map_err(|e| format!("Corrupt {}: {}", path.display(), e).into())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn Error>> {
+    fs::write(path, serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mater-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn loads_are_recorded_per_database() {
+        let root = scratch_dir("archive-loaded");
+        let location = FeedLocation::File(PathBuf::from("2026_fffinal.csv"));
+
+        let mut archive = FeedArchive::open(&root, "scylla:stats@127.0.0.1:9042").unwrap();
+        let sha256 = archive.store("2026_fffinal.csv", &location, b"team,efg\n").unwrap();
+        archive.mark_loaded("2026_fffinal.csv", &sha256).unwrap();
+
+        let reopened = FeedArchive::open(&root, "scylla:stats@127.0.0.1:9042").unwrap();
+        assert!(reopened.is_loaded("2026_fffinal.csv", &sha256));
+        let other = FeedArchive::open(&root, "sqlite:/tmp/mater.db").unwrap();
+        assert!(!other.is_loaded("2026_fffinal.csv", &sha256));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn identical_content_is_stored_once() {
+        let root = scratch_dir("archive-blobs");
+        let location = FeedLocation::File(PathBuf::from("snapshot"));
+        let mut archive = FeedArchive::open(&root, "memory").unwrap();
+        let first = archive.store("2025_fffinal.csv", &location, b"team,efg\n").unwrap();
+        let second = archive.store("2026_fffinal.csv", &location, b"team,efg\n").unwrap();
+        archive.store("2026_fffinal.csv", &location, b"team,efg\n").unwrap();
+        assert_eq!(first, second);
+
+        assert_eq!(fs::read_dir(root.join(BLOB_DIR)).unwrap().count(), 1);
+        for name in ["2025_fffinal.csv", "2026_fffinal.csv"] {
+            assert_eq!(fs::read(archive.day_dir.join(name)).unwrap(), b"team,efg\n");
+            assert_eq!(archive.manifest[name].blob, format!("blobs/{}", first));
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+// src/feed_source.rs
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use flate2::read::GzDecoder;
+use log::info;
+
+use crate::cli::Source;
+use crate::http_client::FetchClient;
+
+const BARTTORVIK_URL: &str = "https://barttorvik.com";
+
+impl Source {
+    /// The name Barttorvik publishes this feed under for a season, which is also the
+    /// file name used inside a `--from-dir` snapshot.
+    pub fn file_name(self, year: i32) -> String {
+        match self {
+            // Served by a PHP endpoint rather than a static file.
+            Source::Players => format!("{}_getadvstats.csv", year),
+            Source::Teams => format!("{}_team_results.json", year),
+            Source::Games => format!("{}_all_advgames.json.gz", year),
+            Source::Schedule => format!("{}_super_sked.csv", year),
+            Source::FourFactors => format!("{}_fffinal.csv", year),
+            Source::PlayerShots => format!("{}_pbp_playerstat_array.json", year),
+            Source::TeamShooting => format!("{}_pbp_teamsstats.json", year),
+            Source::TeamResults => format!("{}_results.csv", year),
+        }
+    }
+
+    /// Where Barttorvik publishes this feed for a season.
+    pub fn url(self, year: i32) -> String {
+        match self {
+            Source::Players => format!("{}/getadvstats.php?year={}&csv=1", BARTTORVIK_URL, year),
+            _ => format!("{}/{}", BARTTORVIK_URL, self.file_name(year)),
+        }
+    }
+}
+
+/// A single feed file, either on Barttorvik (or any other URL) or on local disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedLocation {
+    Url(String),
+    File(PathBuf),
+}
+
+impl FeedLocation {
+    /// Treats `http://` and `https://` values as URLs and anything else as a local path.
+    pub fn parse(value: &str) -> Self {
+        if value.starts_with("http://") || value.starts_with("https://") {
+            FeedLocation::Url(value.to_string())
+        } else {
+            FeedLocation::File(PathBuf::from(value))
+        }
+    }
+
+    /// Reads the feed's bytes exactly as published (gzipped feeds stay compressed).
+    pub async fn read_raw(&self, client: &FetchClient) -> Result<Vec<u8>, Box<dyn Error>> {
+        match self {
+            FeedLocation::Url(url) => client.get(url).await,
+            FeedLocation::File(path) => tokio::fs::read(path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e).into()),
+        }
+    }
+}
+
+/// Decompresses gzip content (detected by its magic bytes, so `.gz` files and gzipped
+/// downloads both work) and passes anything else through unchanged.
+pub fn decompress(raw: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+    if raw.starts_with(&[0x1f, 0x8b]) {
+        info!("Decompressing {} bytes of gzip data...", raw.len());
+        let mut decompressed = Vec::new();
+        GzDecoder::new(&raw[..]).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    } else {
+        Ok(raw)
+    }
+}
+
+impl fmt::Display for FeedLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedLocation::Url(url) => write!(f, "{}", url),
+            FeedLocation::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Decides where each feed is read from during an ingest run.
+///
+/// Per-source `--input` overrides win, then the `--from-dir` snapshot, then Barttorvik.
+#[derive(Debug, Clone, Default)]
+pub struct FeedResolver {
+    from_dir: Option<PathBuf>,
+    inputs: HashMap<Source, String>,
+}
+
+impl FeedResolver {
+    pub fn new(from_dir: Option<PathBuf>, inputs: HashMap<Source, String>) -> Self {
+        FeedResolver { from_dir, inputs }
+    }
+
+    pub fn locate(&self, source: Source, year: i32) -> FeedLocation {
+        if let Some(input) = self.inputs.get(&source) {
+            return FeedLocation::parse(&input.replace("{year}", &year.to_string()));
+        }
+        match &self.from_dir {
+            Some(dir) => FeedLocation::File(snapshot_path(dir, &source.file_name(year))),
+            None => FeedLocation::Url(source.url(year)),
+        }
+    }
+}
+
+// Snapshots may hold a feed either as published or with its gzip layer added/removed,
+// e.g. `2026_all_advgames.json` or `2026_super_sked.csv.gz`. Falls back to the published
+// name so a missing file is reported under the name the user would expect.
+fn snapshot_path(dir: &Path, file_name: &str) -> PathBuf {
+    let candidates = [
+        file_name.to_string(),
+        format!("{}.gz", file_name),
+        file_name.trim_end_matches(".gz").to_string(),
+    ];
+    candidates
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| dir.join(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use std::time::Duration;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use crate::get_four_factors::parse_four_factors_data;
+    use crate::http_client::FetchConfig;
+
+    fn fixtures() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mater-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn decompresses_gzip_by_its_magic_bytes() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"team,efg\n").unwrap();
+        assert_eq!(decompress(encoder.finish().unwrap()).unwrap(), b"team,efg\n");
+
+        // Anything else passes through untouched, whatever the file was called.
+        assert_eq!(decompress(b"team,efg\n".to_vec()).unwrap(), b"team,efg\n");
+        assert_eq!(decompress(vec![0x1f]).unwrap(), vec![0x1f]);
+        assert!(decompress(Vec::new()).unwrap().is_empty());
+
+        assert!(decompress(vec![0x1f, 0x8b, 0x00, 0x01]).is_err());
+    }
+
+    #[test]
+    fn snapshot_files_may_add_or_drop_the_gz_suffix() {
+        let dir = scratch_dir("snapshot-path");
+        assert_eq!(snapshot_path(&dir, "2026_fffinal.csv"), dir.join("2026_fffinal.csv"));
+        assert_eq!(snapshot_path(&dir, "2026_all_advgames.json.gz"), dir.join("2026_all_advgames.json.gz"));
+
+        fs::write(dir.join("2026_fffinal.csv.gz"), b"").unwrap();
+        fs::write(dir.join("2026_all_advgames.json"), b"").unwrap();
+        assert_eq!(snapshot_path(&dir, "2026_fffinal.csv"), dir.join("2026_fffinal.csv.gz"));
+        assert_eq!(snapshot_path(&dir, "2026_all_advgames.json.gz"), dir.join("2026_all_advgames.json"));
+
+        // The published name wins when both are present.
+        fs::write(dir.join("2026_fffinal.csv"), b"").unwrap();
+        assert_eq!(snapshot_path(&dir, "2026_fffinal.csv"), dir.join("2026_fffinal.csv"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn loads_a_gzipped_snapshot() {
+        let dir = fixtures().join("gzipped");
+        let location = FeedResolver::new(Some(dir.clone()), HashMap::new()).locate(Source::FourFactors, 2026);
+        assert_eq!(location, FeedLocation::File(dir.join("2026_fffinal.csv.gz")));
+
+        let client = FetchClient::new(FetchConfig {
+            timeout: Duration::from_secs(1),
+            max_retries: 0,
+            base_backoff: Duration::ZERO,
+            min_interval: Duration::ZERO,
+            cache_dir: None,
+        })
+        .unwrap();
+        let data = decompress(location.read_raw(&client).await.unwrap()).unwrap();
+        assert_eq!(data, fs::read(fixtures().join("snapshot/2026_fffinal.csv")).unwrap());
+
+        let (teams, report) = parse_four_factors_data(&data, 2026).unwrap();
+        assert!(report.rejected.is_empty());
+        assert_eq!(teams.len(), 3);
+    }
+}
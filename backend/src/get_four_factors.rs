@@ -0,0 +1,212 @@
+// src/get_four_factors.rs
+use std::error::Error;
+use log::{info, error};
+use serde::{Deserialize, Serialize};
+use csv::{ReaderBuilder, StringRecord, Reader};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::ingest_report::{IngestReport, csv_raw};
+use crate::schema_drift::DriftDetector;
+
+/// A team's four-factor profile (plus shooting splits) from `fffinal.csv`.
+/// Every `_d` field is the same stat allowed on defense.
+#[derive(Debug, Clone, Deserialize, Serialize, FromRow, SerializeRow)]
+pub struct TeamFourFactors {
+    pub team: String,
+    // Not part of fffinal.csv; filled in from the requested season.
+    #[serde(default)]
+    pub year: i32,
+    pub efg: Option<f64>,
+    pub efg_rank: Option<i32>,
+    pub efg_d: Option<f64>,
+    pub efg_d_rank: Option<i32>,
+    pub ftr: Option<f64>,
+    pub ftr_rank: Option<i32>,
+    pub ftr_d: Option<f64>,
+    pub ftr_d_rank: Option<i32>,
+    pub orb: Option<f64>,
+    pub orb_rank: Option<i32>,
+    pub orb_d: Option<f64>,
+    pub orb_d_rank: Option<i32>,
+    pub tov: Option<f64>,
+    pub tov_rank: Option<i32>,
+    pub tov_d: Option<f64>,
+    pub tov_d_rank: Option<i32>,
+    pub three_pct: Option<f64>,
+    pub three_pct_rank: Option<i32>,
+    pub three_pct_d: Option<f64>,
+    pub three_pct_d_rank: Option<i32>,
+    pub two_pct: Option<f64>,
+    pub two_pct_rank: Option<i32>,
+    pub two_pct_d: Option<f64>,
+    pub two_pct_d_rank: Option<i32>,
+    pub ft_pct: Option<f64>,
+    pub ft_pct_rank: Option<i32>,
+    pub ft_pct_d: Option<f64>,
+    pub ft_pct_d_rank: Option<i32>,
+    pub three_rate: Option<f64>,
+    pub three_rate_rank: Option<i32>,
+    pub three_rate_d: Option<f64>,
+    pub three_rate_d_rank: Option<i32>,
+    pub ast_rate: Option<f64>,
+    pub ast_rate_rank: Option<i32>,
+    pub ast_rate_d: Option<f64>,
+    pub ast_rate_d_rank: Option<i32>,
+}
+
+/// Column layout of `fffinal.csv`. The feed ships its own header row
+/// ("TeamName", "eFG%", "eFG% Rank", ...); it is replaced with these snake_case names.
+const FOUR_FACTORS_HEADERS: [&str; 37] = [
+    "team",
+    "efg", "efg_rank", "efg_d", "efg_d_rank",
+    "ftr", "ftr_rank", "ftr_d", "ftr_d_rank",
+    "orb", "orb_rank", "orb_d", "orb_d_rank",
+    "tov", "tov_rank", "tov_d", "tov_d_rank",
+    "three_pct", "three_pct_rank", "three_pct_d", "three_pct_d_rank",
+    "two_pct", "two_pct_rank", "two_pct_d", "two_pct_d_rank",
+    "ft_pct", "ft_pct_rank", "ft_pct_d", "ft_pct_d_rank",
+    "three_rate", "three_rate_rank", "three_rate_d", "three_rate_d_rank",
+    "ast_rate", "ast_rate_rank", "ast_rate_d", "ast_rate_d_rank",
+];
+
+/// Parses the team four-factors CSV for a single season, checking every row against
+/// `FOUR_FACTORS_HEADERS`.
+pub fn parse_four_factors_data(data: &[u8], year: i32) -> Result<(Vec<TeamFourFactors>, IngestReport), Box<dyn Error>> {
+    info!("Parsing four factors CSV...");
+
+    let headers = StringRecord::from(FOUR_FACTORS_HEADERS.to_vec());
+    let mut drift = DriftDetector::new("four factors", &FOUR_FACTORS_HEADERS, &["team"], &[]);
+
+    let mut reader_builder = ReaderBuilder::new();
+    reader_builder
+        .has_headers(true)
+        .flexible(true)
+        .trim(csv::Trim::All);
+    let mut reader: Reader<&[u8]> = reader_builder.from_reader(data);
+
+    let mut teams: Vec<TeamFourFactors> = Vec::new();
+    let mut report = IngestReport::new("four factors");
+
+    for (i, result) in reader.records().enumerate() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                report.reject(i, String::new(), e.to_string());
+                continue;
+            }
+        };
+        drift.check_csv_record(&record);
+        match record.deserialize::<TeamFourFactors>(Some(&headers)) {
+            Ok(mut team) => {
+                team.year = year;
+                teams.push(team);
+                report.accept();
+            }
+            Err(e) => report.reject(i, csv_raw(&record), e.to_string()),
+        }
+    }
+
+    report.log_summary();
+
+    Ok((teams, report.with_drift(drift.report())))
+}
+
+pub async fn insert_four_factors(
+    session: &Session,
+    teams: &[TeamFourFactors],
+) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO team_four_factors (
+        team, year,
+        efg, efg_rank, efg_d, efg_d_rank,
+        ftr, ftr_rank, ftr_d, ftr_d_rank,
+        orb, orb_rank, orb_d, orb_d_rank,
+        tov, tov_rank, tov_d, tov_d_rank,
+        three_pct, three_pct_rank, three_pct_d, three_pct_d_rank,
+        two_pct, two_pct_rank, two_pct_d, two_pct_d_rank,
+        ft_pct, ft_pct_rank, ft_pct_d, ft_pct_d_rank,
+        three_rate, three_rate_rank, three_rate_d, three_rate_d_rank,
+        ast_rate, ast_rate_rank, ast_rate_d, ast_rate_d_rank
+    ) VALUES (
+        ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?,
+        ?, ?, ?, ?
+    )
+"#;
+
+    write_batched(session, "team_four_factors", query, teams, |t| t.year).await
+}
+
+/// Fetches every team's four factors for a season.
+pub async fn get_four_factors_from_db(
+    session: &Session,
+    year: i32,
+) -> Result<Vec<TeamFourFactors>, QueryError> {
+    let query = r#"
+    SELECT team, year,
+           efg, efg_rank, efg_d, efg_d_rank,
+           ftr, ftr_rank, ftr_d, ftr_d_rank,
+           orb, orb_rank, orb_d, orb_d_rank,
+           tov, tov_rank, tov_d, tov_d_rank,
+           three_pct, three_pct_rank, three_pct_d, three_pct_d_rank,
+           two_pct, two_pct_rank, two_pct_d, two_pct_d_rank,
+           ft_pct, ft_pct_rank, ft_pct_d, ft_pct_d_rank,
+           three_rate, three_rate_rank, three_rate_d, three_rate_d_rank,
+           ast_rate, ast_rate_rank, ast_rate_d, ast_rate_d_rank
+    FROM team_four_factors WHERE year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (year,)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut teams = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match TeamFourFactors::from_row(row) {
+            Ok(t) => teams.push(t),
+            Err(e) => error!("Four factors row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(teams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Every column of the fixture holds a distinct value, so a header mapped one column off
+    // shows up as a wrong value here rather than passing silently.
+    #[test]
+    fn maps_every_column_of_the_fixture() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot/2026_fffinal.csv");
+        let (teams, report) = parse_four_factors_data(&std::fs::read(path).unwrap(), 2026).unwrap();
+
+        assert!(report.rejected.is_empty(), "{:?}", report.rejected);
+        let names: Vec<&str> = teams.iter().map(|t| t.team.as_str()).collect();
+        assert_eq!(names, ["Duke", "Kansas", "Texas Tech"]);
+
+        let duke = &teams[0];
+        assert_eq!(duke.year, 2026);
+        assert_eq!((duke.efg, duke.efg_rank, duke.efg_d, duke.efg_d_rank), (Some(55.1), Some(12), Some(46.3), Some(20)));
+        assert_eq!((duke.ftr_d, duke.ftr_d_rank), (Some(29.8), Some(55)));
+        assert_eq!((duke.tov, duke.tov_rank), (Some(15.2), Some(25)));
+        assert_eq!((duke.three_pct, duke.three_pct_d), (Some(36.9), Some(31.2)));
+        assert_eq!((duke.ft_pct, duke.ft_pct_d_rank), (Some(75.3), Some(150)));
+        assert_eq!((duke.ast_rate, duke.ast_rate_d, duke.ast_rate_d_rank), (Some(55.6), Some(49.1), Some(130)));
+
+        let texas_tech = &teams[2];
+        assert_eq!((texas_tech.orb, texas_tech.orb_d_rank), (Some(28.7), Some(70)));
+        assert_eq!((texas_tech.three_rate, texas_tech.three_rate_d_rank), (Some(38.0), Some(180)));
+        assert_eq!(texas_tech.ast_rate_d_rank, Some(250));
+    }
+}
@@ -1,18 +1,21 @@
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use log::{info, error};
 use serde::{Deserialize, Serialize};
 use scylla::{FromRow, SerializeRow, Session}; // Removed Bytes as it's no longer directly used with query_iter
 use scylla::transport::errors::QueryError;
-use flate2::read::GzDecoder;
-use std::io::Read;
 use std::time::Duration;
 use scylla::query::Query;
 use futures_util::stream::StreamExt; // NEW: Import StreamExt for the .next() method
 
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::ingest_report::{IngestReport, json_raw};
+use crate::schema_drift::DriftDetector;
+
 
 // Helper function to parse a serde_json::Value into an Option<f64>
 // Handles direct numbers and numeric strings, including empty strings for None.
-fn get_opt_f64(value: &serde_json::Value) -> Result<Option<f64>, Box<dyn Error>> {
+pub(crate) fn get_opt_f64(value: &serde_json::Value) -> Result<Option<f64>, Box<dyn Error>> {
     if value.is_f64() {
         Ok(value.as_f64())
     } else if value.is_i64() {
@@ -30,7 +33,7 @@ fn get_opt_f64(value: &serde_json::Value) -> Result<Option<f64>, Box<dyn Error>>
 }
 
 // Helper function to deserialize potentially empty strings or direct numbers into Option<i32>
-fn get_opt_i32(value: &serde_json::Value) -> Result<Option<i32>, Box<dyn Error>> {
+pub(crate) fn get_opt_i32(value: &serde_json::Value) -> Result<Option<i32>, Box<dyn Error>> {
     if value.is_i64() {
         Ok(value.as_i64().map(|i| i as i32))
     } else if value.is_f64() { // Handle floats that might be integers (e.g., 2025.0)
@@ -48,8 +51,7 @@ fn get_opt_i32(value: &serde_json::Value) -> Result<Option<i32>, Box<dyn Error>>
     }
 }
 
-
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, SerializeRow)]
 pub struct GameStats {
     pub numdate: String,
     pub datetext: String,
@@ -106,6 +108,17 @@ pub struct GameStats {
     pub year: Option<i32>,
 }
 
+/// Positional layout of a row in `all_advgames.json`, as read by `GameStats::from_json_array`.
+const GAME_COLUMNS: [&str; 53] = [
+    "numdate", "datetext", "opstyle", "quality", "win1", "opponent", "muid", "win2", // 0 - 7
+    "min_per", "o_rtg", "usage", "e_fg", "ts_per", "orb_per", "drb_per", "ast_per", "to_per", // 8 - 16
+    "dunks_made", "dunks_att", "rim_made", "rim_att", "mid_made", "mid_att", // 17 - 22
+    "two_pm", "two_pa", "tpm", "tpa", "ftm", "fta", // 23 - 28
+    "bpm_rd", "obpm", "dbpm", "bpm_net", "pts", "orb", "drb", "ast", "tov", "stl", "blk", // 29 - 39
+    "stl_per", "blk_per", "pf", "possessions", "bpm", "sbpm", // 40 - 45
+    "loc", "tt", "pp", "inches", "cls", "pid", "year", // 46 - 52
+];
+
 impl GameStats {
     pub fn from_json_array(arr: &[serde_json::Value]) -> Result<Self, Box<dyn Error>> {
         let get_str_val = |idx: usize| -> Result<String, Box<dyn Error>> {
@@ -178,46 +191,36 @@ impl GameStats {
     }
 }
 
-pub async fn get_game_data() -> Result<Vec<GameStats>, Box<dyn Error>> {
-    let url = "https://barttorvik.com/2026_all_advgames.json.gz";
-    info!("Fetching gzipped game data from: {}", url);
-
-    let response = reqwest::get(url).await?.bytes().await?;
-
-    info!("Decompressing game data...");
-    let mut gz_decoder = GzDecoder::new(&response[..]);
-    let mut decompressed_data = String::new();
-    gz_decoder.read_to_string(&mut decompressed_data)?;
+/// Parses every player game line for a single season from the decompressed feed.
+/// Every returned row has its `year` set to `year`.
+pub fn parse_game_data(data: &[u8], year: i32) -> Result<(Vec<GameStats>, IngestReport), Box<dyn Error>> {
+    info!("Parsing game data JSON...");
 
-    info!("Game data decompressed. Parsing JSON...");
-
-    let raw_data: Vec<Vec<serde_json::Value>> = serde_json::from_str(&decompressed_data)?;
+    let raw_data: Vec<Vec<serde_json::Value>> = serde_json::from_slice(data)?;
 
+    let mut drift = DriftDetector::new(
+        "game stats",
+        &GAME_COLUMNS,
+        &["datetext", "opponent", "muid", "loc", "tt", "pp", "cls"],
+        &[],
+    );
     let mut game_stats_records: Vec<GameStats> = Vec::new();
-    let mut error_count = 0;
+    let mut report = IngestReport::new("game stats");
 
     for (i, row) in raw_data.into_iter().enumerate() {
+        drift.check_json_row(&row);
         match GameStats::from_json_array(&row) {
-            Ok(record) => {
+            Ok(mut record) => {
+                record.year = Some(year);
                 game_stats_records.push(record);
+                report.accept();
             }
-            Err(e) => {
-                error_count += 1;
-                if error_count <= 5 {
-                    error!("Error deserializing game row {}: {:?}", i, e);
-                    error!("Problematic row data: {:?}", row);
-                } else if error_count == 6 {
-                    error!("... (further deserialization errors suppressed)");
-                }
-            }
+            Err(e) => report.reject(i, json_raw(&row), e.to_string()),
         }
     }
 
     info!("Game data processing finished.");
-    info!("Successfully parsed and collected {} game records.", game_stats_records.len());
-    if error_count > 0 {
-        info!("Encountered {} errors during deserialization.", error_count);
-    }
+    report.log_summary();
 
     if !game_stats_records.is_empty() {
         info!("\nFirst few game records collected:");
@@ -239,12 +242,12 @@ pub async fn get_game_data() -> Result<Vec<GameStats>, Box<dyn Error>> {
         info!("\nNo game records were collected.");
     }
 
-    Ok(game_stats_records)
+    Ok((game_stats_records, report.with_drift(drift.report())))
 }
 
 pub async fn get_all_game_stats_from_db(
     session: &Session,
-) -> Result<Vec<GameStats>, Box<dyn Error>> {
+) -> Result<Vec<GameStats>, QueryError> {
     info!("Fetching all game stats from database using query_iter...");
     let query_cql = r#"
         SELECT numdate, datetext, opstyle, quality, win1, opponent, muid, win2, min_per, o_rtg, usage,
@@ -252,7 +255,7 @@ pub async fn get_all_game_stats_from_db(
                rim_att, mid_made, mid_att, two_pm, two_pa, tpm, tpa, ftm, fta, bpm_rd, obpm,
                dbpm, bpm_net, pts, orb, drb, ast, tov, stl, blk, stl_per, blk_per, pf,
                possessions, bpm, sbpm, loc, tt, pp, inches, cls, pid, year
-        FROM stats.game_stats
+        FROM game_stats
     "#;
 
     let mut all_game_stats = Vec::new();
@@ -285,7 +288,7 @@ pub async fn get_all_game_stats_from_db(
             },
             Err(e) => {
                 error!("Failed to retrieve row from query_iter (total processed: {}): {}", row_count, e);
-                return Err(Box::new(e)); // Propagate query execution errors
+                return Err(e); // Propagate query execution errors
             }
         }
     }
@@ -294,22 +297,149 @@ pub async fn get_all_game_stats_from_db(
     Ok(all_game_stats)
 }
 
+/// Fetches one player's game lines for a team and season (a single partition).
+pub async fn get_player_game_stats_from_db(
+    session: &Session,
+    pid: i32,
+    year: i32,
+    team: &str,
+) -> Result<Vec<GameStats>, QueryError> {
+    let query = r#"
+        SELECT numdate, datetext, opstyle, quality, win1, opponent, muid, win2, min_per, o_rtg, usage,
+               e_fg, ts_per, orb_per, drb_per, ast_per, to_per, dunks_made, dunks_att, rim_made,
+               rim_att, mid_made, mid_att, two_pm, two_pa, tpm, tpa, ftm, fta, bpm_rd, obpm,
+               dbpm, bpm_net, pts, orb, drb, ast, tov, stl, blk, stl_per, blk_per, pf,
+               possessions, bpm, sbpm, loc, tt, pp, inches, cls, pid, year
+        FROM game_stats WHERE pid = ? AND year = ? AND tt = ?
+    "#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (pid, year, team)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut game_stats = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match GameStats::from_row(row) {
+            Ok(stat) => game_stats.push(stat),
+            Err(e) => error!("Failed to parse row {}: {}", i, e),
+        }
+    }
+    Ok(game_stats)
+}
+
+/// Fetches every game line played for a team in a season (one `game_stats_by_team` partition).
+pub async fn get_team_game_stats_from_db(
+    session: &Session,
+    team: &str,
+    year: i32,
+) -> Result<Vec<GameStats>, QueryError> {
+    let query_cql = r#"
+        SELECT numdate, datetext, opstyle, quality, win1, opponent, muid, win2, min_per, o_rtg, usage,
+               e_fg, ts_per, orb_per, drb_per, ast_per, to_per, dunks_made, dunks_att, rim_made,
+               rim_att, mid_made, mid_att, two_pm, two_pa, tpm, tpa, ftm, fta, bpm_rd, obpm,
+               dbpm, bpm_net, pts, orb, drb, ast, tov, stl, blk, stl_per, blk_per, pf,
+               possessions, bpm, sbpm, loc, tt, pp, inches, cls, pid, year
+        FROM game_stats_by_team WHERE tt = ? AND year = ?
+    "#;
+
+    let mut query = Query::new(query_cql);
+    query.set_page_size(5000);
+
+    let mut rows_iter = session.query_iter(query, (team, year)).await?;
+    let mut team_games = Vec::new();
+    while let Some(row_res) = rows_iter.next().await {
+        match GameStats::from_row(row_res?) {
+            Ok(stat) => team_games.push(stat),
+            Err(e) => error!("Failed to parse game stats row for {} {}: {}", team, year, e),
+        }
+    }
+    Ok(team_games)
+}
+
+/// Fetches every stored game for one season, a team partition at a time.
+pub async fn get_season_game_stats_from_db(
+    session: &Session,
+    year: i32,
+) -> Result<Vec<GameStats>, QueryError> {
+    info!("Fetching stored game stats for {}...", year);
+    let result = session.query("SELECT team FROM game_teams WHERE year = ?", (year,)).await?;
+    let mut teams: Vec<String> = Vec::new();
+    for row in result.rows.unwrap_or_default() {
+        match row.into_typed::<(String,)>() {
+            Ok((team,)) => teams.push(team),
+            Err(e) => error!("Failed to parse game_teams row for {}: {}", year, e),
+        }
+    }
+
+    let mut season_games = Vec::new();
+    for team in &teams {
+        season_games.extend(get_team_game_stats_from_db(session, team, year).await?);
+    }
+
+    info!("Fetched {} stored game stats for {} from {} teams", season_games.len(), year, teams.len());
+    Ok(season_games)
+}
+
+/// A row's primary key in `game_stats`: (pid, year, tt, numdate).
+pub type GameKey = (Option<i32>, Option<i32>, String, String);
+
+impl GameStats {
+    pub fn key(&self) -> GameKey {
+        (self.pid, self.year, self.tt.clone(), self.numdate.clone())
+    }
+}
+
+/// How a parsed feed compares with the games already stored for its season.
+#[derive(Debug, Default)]
+pub struct GameDiff {
+    /// Games that are new or differ from the stored row; only these need writing.
+    pub to_write: Vec<GameStats>,
+    pub new_games: usize,
+    pub changed_games: usize,
+    pub unchanged_games: usize,
+}
+
+/// Splits `parsed` into the games `game_stats` lacks or holds a different version of,
+/// and those it already has verbatim.
+pub fn diff_games(parsed: Vec<GameStats>, stored: &[GameStats]) -> GameDiff {
+    let stored: HashMap<GameKey, &GameStats> = stored.iter().map(|g| (g.key(), g)).collect();
+
+    let mut diff = GameDiff::default();
+    for game in parsed {
+        match stored.get(&game.key()) {
+            None => diff.new_games += 1,
+            Some(&existing) if *existing != game => diff.changed_games += 1,
+            Some(_) => {
+                diff.unchanged_games += 1;
+                continue;
+            }
+        }
+        diff.to_write.push(game);
+    }
+    diff
+}
+
 pub async fn insert_game_stats(
     session: &Session,
     games: &[GameStats],
-) -> Result<(), QueryError> {
+) -> Result<WriteReport, QueryError> {
     let query = r#"
-    INSERT INTO stats.game_stats (
+    INSERT INTO game_stats (
         numdate, datetext, opstyle, quality, win1, opponent, muid, win2, min_per, o_rtg, usage, e_fg, ts_per, orb_per, drb_per, ast_per, to_per, dunks_made, dunks_att, rim_made, rim_att, mid_made, mid_att, two_pm, two_pa, tpm, tpa, ftm, fta, bpm_rd, obpm, dbpm, bpm_net, pts, orb, drb, ast, tov, stl, blk, stl_per, blk_per, pf, possessions, bpm, sbpm, loc, tt, pp, inches, cls, pid, year
     ) VALUES (
         ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
     )
 "#;
 
-    let prepared = session.prepare(query).await?;
-    for g in games {
-        session.execute(&prepared, &g).await?;
-    }
+    let report = write_batched(session, "game_stats", query, games, |g| (g.pid, g.year, g.tt.clone())).await?;
+
+    // The same rows again, partitioned by team for the team and season reads.
+    let by_team_query = query.replace("INSERT INTO game_stats (", "INSERT INTO game_stats_by_team (");
+    write_batched(session, "game_stats_by_team", &by_team_query, games, |g| (g.tt.clone(), g.year)).await?;
+
+    let teams: HashSet<(Option<i32>, String)> = games.iter().map(|g| (g.year, g.tt.clone())).collect();
+    let teams: Vec<(Option<i32>, String)> = teams.into_iter().collect();
+    write_batched(session, "game_teams", "INSERT INTO game_teams (year, team) VALUES (?, ?)", &teams, |t| t.0).await?;
 
-    Ok(())
+    Ok(report)
 }
@@ -0,0 +1,280 @@
+// src/get_player_shots.rs
+use std::error::Error;
+use log::error;
+use serde::{Deserialize, Serialize};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::get_game_stats::{get_opt_f64, get_opt_i32};
+use crate::ingest_report::{IngestReport, json_raw};
+use crate::schema_drift::DriftDetector;
+
+/// A player's season shot diet by distance, from `pbp_playerstat_array.json`.
+///
+/// Rim attempts include dunks. The `_share` fields are each zone's fraction of all field goal
+/// attempts, and the `_assisted_pct` fields the fraction of makes in that zone that were assisted.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct PlayerShotProfile {
+    pub pid: i32,
+    pub year: i32,
+    pub player_name: String,
+    pub team: String,
+    pub dunks_made: Option<i32>,
+    pub dunks_att: Option<i32>,
+    pub dunks_assisted: Option<i32>,
+    pub rim_made: Option<i32>,
+    pub rim_att: Option<i32>,
+    pub rim_assisted: Option<i32>,
+    pub mid_made: Option<i32>,
+    pub mid_att: Option<i32>,
+    pub mid_assisted: Option<i32>,
+    pub three_made: Option<i32>,
+    pub three_att: Option<i32>,
+    pub three_assisted: Option<i32>,
+    pub rim_pct: Option<f64>,
+    pub mid_pct: Option<f64>,
+    pub three_pct: Option<f64>,
+    pub rim_share: Option<f64>,
+    pub mid_share: Option<f64>,
+    pub three_share: Option<f64>,
+    pub rim_assisted_pct: Option<f64>,
+    pub mid_assisted_pct: Option<f64>,
+    pub three_assisted_pct: Option<f64>,
+}
+
+/// Positional layout of a row in `pbp_playerstat_array.json`.
+const PLAYER_SHOT_COLUMNS: [&str; 15] = [
+    "pid", "player_name", "team",
+    "dunks_made", "dunks_att", "dunks_assisted", "rim_made", "rim_att", "rim_assisted",
+    "mid_made", "mid_att", "mid_assisted", "three_made", "three_att", "three_assisted",
+];
+
+// Shot counts in the play-by-play feeds occasionally arrive as floats (e.g. 12.0), as
+// numbers or strings; accept either as long as it is a whole count. Anything else (12.5,
+// NaN, out of range) is an error, so the row is rejected rather than silently truncated.
+pub(crate) fn get_opt_count(value: &serde_json::Value) -> Result<Option<i32>, Box<dyn Error>> {
+    match get_opt_f64(value)? {
+        Some(v) if v.is_finite() && v.fract() == 0.0 && (i32::MIN as f64..=i32::MAX as f64).contains(&v) => {
+            Ok(Some(v as i32))
+        }
+        Some(_) => Err(format!("Expected a whole count, found {}", value).into()),
+        None => Ok(None),
+    }
+}
+
+// Sums a row's zone attempts, treating missing zones as zero. A total past `i32::MAX` is an
+// error rather than a wrap, so the row is rejected instead of producing nonsense shares.
+pub(crate) fn total_attempts(zones: &[Option<i32>]) -> Result<Option<i32>, Box<dyn Error>> {
+    zones
+        .iter()
+        .try_fold(0i32, |total, att| total.checked_add(att.unwrap_or_default()))
+        .map(Some)
+        .ok_or_else(|| "Total field goal attempts overflow".into())
+}
+
+// Returns `num / den`, or `None` when there is nothing to divide by.
+pub(crate) fn ratio(num: Option<i32>, den: Option<i32>) -> Option<f64> {
+    match (num, den) {
+        (Some(n), Some(d)) if d > 0 => Some(n as f64 / d as f64),
+        _ => None,
+    }
+}
+
+impl PlayerShotProfile {
+    /// Parses one player row. Layout:
+    /// `[pid, player_name, team, dunks_made, dunks_att, dunks_assisted, rim_made, rim_att, rim_assisted,
+    ///   mid_made, mid_att, mid_assisted, three_made, three_att, three_assisted]`
+    pub fn from_json_array(arr: &[serde_json::Value], year: i32) -> Result<Self, Box<dyn Error>> {
+        let get_str_val = |idx: usize| -> Result<String, Box<dyn Error>> {
+            arr.get(idx)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| format!("Missing or invalid string at index {}", idx).into())
+        };
+
+        let get_raw_val = |idx: usize| -> &serde_json::Value {
+            arr.get(idx).unwrap_or(&serde_json::Value::Null)
+        };
+
+        let pid = get_opt_i32(get_raw_val(0))?.ok_or("Missing pid at index 0")?;
+
+        let count = |idx: usize| get_opt_count(get_raw_val(idx));
+
+        let dunks_made = count(3)?;
+        let dunks_att = count(4)?;
+        let dunks_assisted = count(5)?;
+        let rim_made = count(6)?;
+        let rim_att = count(7)?;
+        let rim_assisted = count(8)?;
+        let mid_made = count(9)?;
+        let mid_att = count(10)?;
+        let mid_assisted = count(11)?;
+        let three_made = count(12)?;
+        let three_att = count(13)?;
+        let three_assisted = count(14)?;
+
+        let total_att = total_attempts(&[rim_att, mid_att, three_att])?;
+
+        Ok(PlayerShotProfile {
+            pid,
+            year,
+            player_name: get_str_val(1)?,
+            team: get_str_val(2)?,
+            dunks_made,
+            dunks_att,
+            dunks_assisted,
+            rim_made,
+            rim_att,
+            rim_assisted,
+            mid_made,
+            mid_att,
+            mid_assisted,
+            three_made,
+            three_att,
+            three_assisted,
+            rim_pct: ratio(rim_made, rim_att),
+            mid_pct: ratio(mid_made, mid_att),
+            three_pct: ratio(three_made, three_att),
+            rim_share: ratio(rim_att, total_att),
+            mid_share: ratio(mid_att, total_att),
+            three_share: ratio(three_att, total_att),
+            rim_assisted_pct: ratio(rim_assisted, rim_made),
+            mid_assisted_pct: ratio(mid_assisted, mid_made),
+            three_assisted_pct: ratio(three_assisted, three_made),
+        })
+    }
+}
+
+/// Parses every player's shot-distance profile for a single season.
+pub fn parse_player_shot_data(data: &[u8], year: i32) -> Result<(Vec<PlayerShotProfile>, IngestReport), Box<dyn Error>> {
+    let raw_data: Vec<Vec<serde_json::Value>> = serde_json::from_slice(data)?;
+
+    let mut drift = DriftDetector::new("player shots", &PLAYER_SHOT_COLUMNS, &["player_name", "team"], &[]);
+    let mut profiles: Vec<PlayerShotProfile> = Vec::new();
+    let mut report = IngestReport::new("player shots");
+
+    for (i, row) in raw_data.into_iter().enumerate() {
+        drift.check_json_row(&row);
+        match PlayerShotProfile::from_json_array(&row, year) {
+            Ok(record) => {
+                profiles.push(record);
+                report.accept();
+            }
+            Err(e) => report.reject(i, json_raw(&row), e.to_string()),
+        }
+    }
+
+    report.log_summary();
+
+    Ok((profiles, report.with_drift(drift.report())))
+}
+
+pub async fn insert_player_shot_profiles(
+    session: &Session,
+    profiles: &[PlayerShotProfile],
+) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO player_shot_profiles (
+        pid, year, player_name, team,
+        dunks_made, dunks_att, dunks_assisted, rim_made, rim_att, rim_assisted,
+        mid_made, mid_att, mid_assisted, three_made, three_att, three_assisted,
+        rim_pct, mid_pct, three_pct, rim_share, mid_share, three_share,
+        rim_assisted_pct, mid_assisted_pct, three_assisted_pct
+    ) VALUES (
+        ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?,
+        ?, ?, ?
+    )
+"#;
+
+    write_batched(session, "player_shot_profiles", query, profiles, |p| (p.team.clone(), p.year)).await
+}
+
+/// Fetches the shot profiles of every player on a team in a season.
+pub async fn get_player_shot_profiles_from_db(
+    session: &Session,
+    team: &str,
+    year: i32,
+) -> Result<Vec<PlayerShotProfile>, QueryError> {
+    let query = r#"
+    SELECT pid, year, player_name, team,
+           dunks_made, dunks_att, dunks_assisted, rim_made, rim_att, rim_assisted,
+           mid_made, mid_att, mid_assisted, three_made, three_att, three_assisted,
+           rim_pct, mid_pct, three_pct, rim_share, mid_share, three_share,
+           rim_assisted_pct, mid_assisted_pct, three_assisted_pct
+    FROM player_shot_profiles WHERE team = ? AND year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (team, year)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut profiles = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match PlayerShotProfile::from_row(row) {
+            Ok(p) => profiles.push(p),
+            Err(e) => error!("Shot profile row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn counts_must_be_whole_numbers() {
+        assert_eq!(get_opt_count(&json!(12)).unwrap(), Some(12));
+        assert_eq!(get_opt_count(&json!(12.0)).unwrap(), Some(12));
+        assert_eq!(get_opt_count(&json!("7.0")).unwrap(), Some(7));
+        assert_eq!(get_opt_count(&json!("")).unwrap(), None);
+        assert_eq!(get_opt_count(&json!(null)).unwrap(), None);
+
+        for bad in [json!(12.5), json!("NaN"), json!("inf"), json!(3e10), json!("abc")] {
+            assert!(get_opt_count(&bad).is_err(), "{} was accepted", bad);
+        }
+    }
+
+    #[test]
+    fn derives_shares_and_percentages() {
+        let row = json!([4242, "Jane Doe", "Duke", 5, 6, 4, 40, 60, 20, 10, 25, 2, 12, 15, 9]);
+        let profile = PlayerShotProfile::from_json_array(row.as_array().unwrap(), 2026).unwrap();
+
+        assert_eq!(profile.pid, 4242);
+        assert_eq!(profile.team, "Duke");
+        assert_eq!(profile.rim_pct, Some(40.0 / 60.0));
+        assert_eq!(profile.mid_pct, Some(10.0 / 25.0));
+        assert_eq!(profile.three_pct, Some(12.0 / 15.0));
+        assert_eq!(profile.rim_share, Some(60.0 / 100.0));
+        assert_eq!(profile.mid_share, Some(25.0 / 100.0));
+        assert_eq!(profile.three_share, Some(15.0 / 100.0));
+        assert_eq!(profile.rim_assisted_pct, Some(20.0 / 40.0));
+        assert_eq!(profile.three_assisted_pct, Some(9.0 / 12.0));
+
+        // A player without a single attempt has no shares rather than dividing by zero.
+        let row = json!([5151, "Sam Roe", "Duke", 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let profile = PlayerShotProfile::from_json_array(row.as_array().unwrap(), 2026).unwrap();
+        assert_eq!(profile.rim_share, None);
+        assert_eq!(profile.three_pct, None);
+    }
+
+    #[test]
+    fn rejects_attempt_totals_past_i32_max() {
+        let data = json!([
+            [4242, "Jane Doe", "Duke", 0, 0, 0, 0, i32::MAX - 10, 0, 0, 11, 0, 0, 0, 0],
+            [5151, "Sam Roe", "Duke", 0, 0, 0, 0, i32::MAX - 10, 0, 0, 10, 0, 0, 0, 0],
+        ]);
+        let (profiles, report) = parse_player_shot_data(data.to_string().as_bytes(), 2026).unwrap();
+
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].pid, 5151);
+        assert_eq!(profiles[0].rim_share, Some((i32::MAX - 10) as f64 / i32::MAX as f64));
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].index, 0);
+    }
+}
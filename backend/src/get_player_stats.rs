@@ -1,11 +1,15 @@
 // src/get_player_stats.rs
 use std::error::Error;
-use log::{info, error};
+use log::info;
 use serde::{Deserialize, Serialize};
 use csv::{ReaderBuilder, StringRecord, Reader};
 use scylla::{FromRow, SerializeRow, Session};
 use scylla::transport::errors::QueryError;
 
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::ingest_report::{IngestReport, csv_raw};
+use crate::schema_drift::DriftDetector;
+
 #[derive(Debug, Clone, Deserialize, Serialize, FromRow, SerializeRow)]
 pub struct PlayerStats {
     pub player_name: String,
@@ -74,56 +78,64 @@ pub struct PlayerStats {
     pub pts: Option<f64>,
 }
 
-pub async fn get_player_data() -> Result<Vec<PlayerStats>, Box<dyn Error>> {
-    let url = "https://barttorvik.com/getadvstats.php?year=2026&csv=1";
-    info!("Fetching data from: {}", url);
-    let csv_data = reqwest::get(url).await?.text().await?;
-    info!("Data fetched successfully. Parsing CSV...");
-
-    let headers = StringRecord::from(vec![
-        "player_name", "team", "conf", "gp", "min_per", "o_rtg", "usg", "e_fg", "ts_per",
-        "orb_per", "drb_per", "ast_per", "to_per", "ftm", "fta", "ft_per", "two_pm", "two_pa",
-        "two_p_per", "tpm", "tpa", "tp_per", "blk_per", "stl_per", "ftr", "yr", "ht", "num",
-        "porpag", "adjoe", "pfr", "year", "pid", "player_type",
-        "rec_rank", "ast_tov", "rim_made", "rim_attempted", "mid_made", "mid_attempted",
-        "rim_pct", "mid_pct", "dunks_made", "dunks_attempted", "dunk_pct", "pick", "drtg",
-        "adrtg", "dporpag", "stops", "bpm", "obpm", "dbpm", "gbpm", "mp", "ogbpm", "dgbpm",
-        "oreb", "dreb", "treb", "ast", "stl", "blk", "pts"
-    ]);
+/// Column layout of the advanced player stats CSV (no header row).
+const PLAYER_HEADERS: [&str; 64] = [
+    "player_name", "team", "conf", "gp", "min_per", "o_rtg", "usg", "e_fg", "ts_per",
+    "orb_per", "drb_per", "ast_per", "to_per", "ftm", "fta", "ft_per", "two_pm", "two_pa",
+    "two_p_per", "tpm", "tpa", "tp_per", "blk_per", "stl_per", "ftr", "yr", "ht", "num",
+    "porpag", "adjoe", "pfr", "year", "pid", "player_type",
+    "rec_rank", "ast_tov", "rim_made", "rim_attempted", "mid_made", "mid_attempted",
+    "rim_pct", "mid_pct", "dunks_made", "dunks_attempted", "dunk_pct", "pick", "drtg",
+    "adrtg", "dporpag", "stops", "bpm", "obpm", "dbpm", "gbpm", "mp", "ogbpm", "dgbpm",
+    "oreb", "dreb", "treb", "ast", "stl", "blk", "pts"
+];
+
+/// Parses the advanced player stats CSV for a single season, checking every row against
+/// `PLAYER_HEADERS`. Every returned row has its `year` set to `year`.
+pub fn parse_player_data(data: &[u8], year: i32) -> Result<(Vec<PlayerStats>, IngestReport), Box<dyn Error>> {
+    info!("Parsing player stats CSV...");
+
+    let headers = StringRecord::from(PLAYER_HEADERS.to_vec());
+    let mut drift = DriftDetector::new(
+        "player stats",
+        &PLAYER_HEADERS,
+        &["player_name", "team", "conf"],
+        &["yr", "ht", "num", "player_type"],
+    );
 
     let mut reader_builder = ReaderBuilder::new();
     reader_builder
         .has_headers(false)
+        .flexible(true)
         .trim(csv::Trim::All);
-    let mut reader: Reader<&[u8]> = reader_builder.from_reader(csv_data.as_bytes());
-    reader.set_headers(headers);
+    let mut reader: Reader<&[u8]> = reader_builder.from_reader(data);
 
     info!("Deserializing rows into PlayerStats struct using snake_case headers:");
 
     let mut players: Vec<PlayerStats> = Vec::new();
-    let mut error_count = 0;
+    let mut report = IngestReport::new("player stats");
 
-    for result in reader.deserialize::<PlayerStats>() {
-        match result {
-            Ok(record) => {
-                players.push(record);
-            }
+    for (i, result) in reader.records().enumerate() {
+        let record = match result {
+            Ok(record) => record,
             Err(e) => {
-                error_count += 1;
-                if error_count <= 5 {
-                    error!("Error deserializing row: {}", e);
-                } else if error_count == 6 {
-                    error!("... (further deserialization errors suppressed)");
-                }
+                report.reject(i, String::new(), e.to_string());
+                continue;
             }
+        };
+        drift.check_csv_record(&record);
+        match record.deserialize::<PlayerStats>(Some(&headers)) {
+            Ok(mut player) => {
+                player.year = Some(year);
+                players.push(player);
+                report.accept();
+            }
+            Err(e) => report.reject(i, csv_raw(&record), e.to_string()),
         }
     }
 
     info!("CSV processing finished.");
-    info!("Successfully parsed and collected {} player records.", players.len());
-    if error_count > 0 {
-        info!("Encountered {} errors during deserialization.", error_count);
-    }
+    report.log_summary();
 
     if !players.is_empty() {
         info!("\nFirst few players collected:");
@@ -145,15 +157,15 @@ pub async fn get_player_data() -> Result<Vec<PlayerStats>, Box<dyn Error>> {
         info!("\nNo players were collected.");
     }
 
-    Ok(players)
+    Ok((players, report.with_drift(drift.report())))
 }
 
 pub async fn insert_player_stats(
     session: &Session,
     players: &[PlayerStats],
-) -> Result<(), QueryError> {
+) -> Result<WriteReport, QueryError> {
     let query = r#"
-    INSERT INTO stats.player_stats (
+    INSERT INTO player_stats (
         player_name, team, conf, gp, min_per, o_rtg, usg, e_fg, ts_per, orb_per,
         drb_per, ast_per, to_per, ftm, fta, ft_per, two_pm, two_pa, two_p_per,
         tpm, tpa, tp_per, blk_per, stl_per, ftr, yr, ht, num, porpag, adjoe, pfr,
@@ -172,10 +184,36 @@ pub async fn insert_player_stats(
     )
 "#;
 
-    let prepared = session.prepare(query).await?;
-    for p in players {
-        session.execute(&prepared, &p).await?;
-    }
+    let report = write_batched(session, "player_stats", query, players, |p| (p.team.clone(), p.year)).await?;
+
+    // The same rows again, partitioned by player. pid is that table's partition key, so
+    // rows without one only go to player_stats.
+    let by_pid_query = query.replace("INSERT INTO player_stats (", "INSERT INTO player_stats_by_pid (");
+    let with_pid: Vec<&PlayerStats> = players.iter().filter(|p| p.pid.is_some()).collect();
+    write_batched(session, "player_stats_by_pid", &by_pid_query, &with_pid, |p| (p.pid, p.year)).await?;
 
-    Ok(())
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/snapshot/2026_getadvstats.csv");
+
+    #[test]
+    fn short_rows_reach_the_drift_detector() {
+        let mut lines: Vec<String> = FIXTURE.lines().map(str::to_string).collect();
+        let short: Vec<&str> = lines[0].split(',').take(60).collect();
+        lines[0] = short.join(",");
+
+        let (players, report) = parse_player_data(lines.join("\n").as_bytes(), 2026).unwrap();
+        // The missing trailing columns are all optional, so the row still loads.
+        assert_eq!(players.len(), 3);
+        assert!(players[0].pts.is_none());
+
+        let drift = report.drift.unwrap();
+        assert_eq!((drift.rows_checked, drift.drifted_rows), (3, 1));
+        assert_eq!(drift.widths_seen.get(&60), Some(&1));
+    }
 }
@@ -0,0 +1,222 @@
+// src/get_schedule.rs
+use std::collections::HashMap;
+use std::error::Error;
+use log::{info, error};
+use serde::{Deserialize, Serialize};
+use csv::{ReaderBuilder, StringRecord, Reader};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::ingest_report::{IngestReport, csv_raw};
+use crate::schema_drift::DriftDetector;
+
+/// One matchup from `super_sked.csv`, the full-season schedule with pregame predictions and results.
+/// `muid` matches `GameStats::muid`, so player game lines can be joined to the game itself.
+#[derive(Debug, Clone, Deserialize, Serialize, FromRow, SerializeRow)]
+pub struct ScheduleGame {
+    pub muid: String,
+    // Not part of super_sked.csv; filled in from the requested season.
+    #[serde(default)]
+    pub year: i32,
+    pub date: Option<String>,
+    pub conmatch: Option<String>,
+    pub matchup: Option<String>,
+    pub prediction: Option<String>,
+    pub ttq: Option<String>,
+    pub conf: Option<String>,
+    pub venue: Option<String>,
+    pub team1: Option<String>,
+    pub t1oe: Option<f64>,
+    pub t1de: Option<f64>,
+    pub t1py: Option<f64>,
+    pub t1wp: Option<f64>,
+    pub t1propt: Option<f64>,
+    pub team2: Option<String>,
+    pub t2oe: Option<f64>,
+    pub t2de: Option<f64>,
+    pub t2py: Option<f64>,
+    pub t2wp: Option<f64>,
+    pub t2propt: Option<f64>,
+    pub tpro: Option<f64>,
+    pub t1qual: Option<f64>,
+    pub t2qual: Option<f64>,
+    pub gp: Option<i32>,
+    pub result: Option<String>,
+    pub tempo: Option<f64>,
+    pub possessions: Option<f64>,
+    pub t1pts: Option<i32>,
+    pub t2pts: Option<i32>,
+    pub winner: Option<String>,
+    pub loser: Option<String>,
+    pub t1adjt: Option<f64>,
+    pub t2adjt: Option<f64>,
+    pub t1adjo: Option<f64>,
+    pub t1adjd: Option<f64>,
+    pub t2adjo: Option<f64>,
+    pub t2adjd: Option<f64>,
+    pub gamevalue: Option<f64>,
+    pub mismatch: Option<f64>,
+    pub blowout: Option<f64>,
+    pub t1elite: Option<f64>,
+    pub t2elite: Option<f64>,
+    pub ord_date: Option<f64>,
+    pub t1ppp: Option<f64>,
+    pub t2ppp: Option<f64>,
+    pub gameppp: Option<f64>,
+    pub t1rk: Option<i32>,
+    pub t2rk: Option<i32>,
+    pub t1gs: Option<f64>,
+    pub t2gs: Option<f64>,
+    pub gamestats: Option<String>,
+    pub overtimes: Option<i32>,
+    pub t1fun: Option<f64>,
+    pub t2fun: Option<f64>,
+    pub results: Option<String>,
+}
+
+/// Column layout of `super_sked.csv` (55 columns, no header row).
+const SCHEDULE_HEADERS: [&str; 55] = [
+    "muid", "date", "conmatch", "matchup", "prediction", "ttq", "conf", "venue", // 0 - 7
+    "team1", "t1oe", "t1de", "t1py", "t1wp", "t1propt", "team2", "t2oe", "t2de", "t2py", "t2wp", // 8 - 18
+    "t2propt", "tpro", "t1qual", "t2qual", "gp", "result", "tempo", "possessions", "t1pts", // 19 - 27
+    "t2pts", "winner", "loser", "t1adjt", "t2adjt", "t1adjo", "t1adjd", "t2adjo", "t2adjd", // 28 - 36
+    "gamevalue", "mismatch", "blowout", "t1elite", "t2elite", "ord_date", "t1ppp", "t2ppp", "gameppp", // 37 - 45
+    "t1rk", "t2rk", "t1gs", "t2gs", "gamestats", "overtimes", "t1fun", "t2fun", "results", // 46 - 54
+];
+
+/// Parses the full schedule for a single season, checking every row against `SCHEDULE_HEADERS`.
+/// Rows are keyed on `muid`; if the feed repeats a matchup the last row wins.
+pub fn parse_schedule_data(data: &[u8], year: i32) -> Result<(Vec<ScheduleGame>, IngestReport), Box<dyn Error>> {
+    info!("Parsing schedule CSV...");
+
+    let headers = StringRecord::from(SCHEDULE_HEADERS.to_vec());
+    let mut drift = DriftDetector::new(
+        "schedule",
+        &SCHEDULE_HEADERS,
+        &["muid", "team1", "team2", "winner", "loser"],
+        &["date", "conmatch", "matchup", "prediction", "ttq", "conf", "venue", "result", "gamestats", "results"],
+    );
+
+    let mut reader_builder = ReaderBuilder::new();
+    reader_builder
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All);
+    let mut reader: Reader<&[u8]> = reader_builder.from_reader(data);
+
+    let mut games_by_muid: HashMap<String, ScheduleGame> = HashMap::new();
+    let mut report = IngestReport::new("schedule");
+
+    for (i, result) in reader.records().enumerate() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                report.reject(i, String::new(), e.to_string());
+                continue;
+            }
+        };
+        drift.check_csv_record(&record);
+        match record.deserialize::<ScheduleGame>(Some(&headers)) {
+            Ok(game) if game.muid.is_empty() => report.reject(i, csv_raw(&record), "Missing muid".to_string()),
+            Ok(mut game) => {
+                game.year = year;
+                games_by_muid.insert(game.muid.clone(), game);
+                report.accept();
+            }
+            Err(e) => report.reject(i, csv_raw(&record), e.to_string()),
+        }
+    }
+
+    report.log_summary();
+    info!("Collected {} unique scheduled games.", games_by_muid.len());
+
+    Ok((games_by_muid.into_values().collect(), report.with_drift(drift.report())))
+}
+
+pub async fn insert_schedule_games(
+    session: &Session,
+    games: &[ScheduleGame],
+) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO schedule_games (
+        muid, year, date, conmatch, matchup, prediction, ttq, conf, venue,
+        team1, t1oe, t1de, t1py, t1wp, t1propt, team2, t2oe, t2de, t2py, t2wp,
+        t2propt, tpro, t1qual, t2qual, gp, result, tempo, possessions, t1pts,
+        t2pts, winner, loser, t1adjt, t2adjt, t1adjo, t1adjd, t2adjo, t2adjd,
+        gamevalue, mismatch, blowout, t1elite, t2elite, ord_date, t1ppp, t2ppp, gameppp,
+        t1rk, t2rk, t1gs, t2gs, gamestats, overtimes, t1fun, t2fun, results
+    ) VALUES (
+        ?, ?, ?, ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?, ?, ?, ?
+    )
+"#;
+
+    write_batched(session, "schedule_games", query, games, |g| (g.muid.clone(), g.year)).await
+}
+
+/// Looks up a single scheduled game by the `muid` carried on `game_stats` rows.
+pub async fn get_schedule_game_from_db(
+    session: &Session,
+    muid: &str,
+    year: i32,
+) -> Result<Option<ScheduleGame>, QueryError> {
+    let query = r#"
+    SELECT muid, year, date, conmatch, matchup, prediction, ttq, conf, venue,
+           team1, t1oe, t1de, t1py, t1wp, t1propt, team2, t2oe, t2de, t2py, t2wp,
+           t2propt, tpro, t1qual, t2qual, gp, result, tempo, possessions, t1pts,
+           t2pts, winner, loser, t1adjt, t2adjt, t1adjo, t1adjd, t2adjo, t2adjd,
+           gamevalue, mismatch, blowout, t1elite, t2elite, ord_date, t1ppp, t2ppp, gameppp,
+           t1rk, t2rk, t1gs, t2gs, gamestats, overtimes, t1fun, t2fun, results
+    FROM schedule_games WHERE muid = ? AND year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (muid, year)).await?;
+
+    match result.rows.unwrap_or_default().into_iter().next() {
+        Some(row) => match ScheduleGame::from_row(row) {
+            Ok(game) => Ok(Some(game)),
+            Err(e) => {
+                error!("Scheduled game {} {} failed to convert: {}", muid, year, e);
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn keeps_the_last_row_per_muid_and_rejects_empty_ones() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot/2026_super_sked.csv");
+        let (mut games, report) = parse_schedule_data(&std::fs::read(path).unwrap(), 2026).unwrap();
+        games.sort_by(|a, b| a.muid.cmp(&b.muid));
+
+        let muids: Vec<&str> = games.iter().map(|g| g.muid.as_str()).collect();
+        assert_eq!(muids, ["Duke-Iowa St.-20251110", "North Carolina-Kansas-20251110"]);
+        assert!(games.iter().all(|g| g.year == 2026));
+
+        // The repeated matchup keeps its later, corrected score.
+        let unc = &games[1];
+        assert_eq!((unc.t1pts, unc.t2pts), (Some(79), Some(70)));
+        assert_eq!(unc.result.as_deref(), Some("North Carolina 79-70"));
+        assert_eq!((unc.team1.as_deref(), unc.team2.as_deref()), (Some("North Carolina"), Some("Kansas")));
+        assert_eq!((unc.t1oe, unc.t2de, unc.tempo), (Some(115.2), Some(97.0), Some(69.8)));
+        assert_eq!((unc.t1rk, unc.overtimes), (Some(8), Some(0)));
+        assert_eq!(unc.gamestats, None);
+
+        assert_eq!(report.rows_accepted, 3);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].index, 3);
+        assert_eq!(report.rejected[0].reason, "Missing muid");
+    }
+}
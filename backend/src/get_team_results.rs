@@ -0,0 +1,251 @@
+// src/get_team_results.rs
+use std::error::Error;
+use log::{info, error};
+use serde::{Deserialize, Serialize};
+use csv::{ReaderBuilder, StringRecord};
+use chrono::NaiveDate;
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::ingest_report::{IngestReport, csv_raw};
+use crate::schema_drift::DriftDetector;
+
+/// One team's line for one game from `results.csv`: opponent, venue, final score and
+/// the game's adjusted/raw efficiency and four factors on both ends.
+#[derive(Debug, Clone, Deserialize, Serialize, FromRow, SerializeRow)]
+pub struct TeamGameResult {
+    pub team: String,
+    // Not part of results.csv; filled in from the requested season.
+    #[serde(default)]
+    pub year: i32,
+    // `date` normalized to YYYYMMDD so game logs sort chronologically, like `GameStats::numdate`.
+    #[serde(default)]
+    pub numdate: String,
+    pub date: String,
+    pub game_type: Option<String>,
+    pub conf: Option<String>,
+    pub opponent: String,
+    pub location: Option<String>,
+    pub result: Option<String>,
+    // Parsed out of `result` (e.g. "W, 78-70").
+    #[serde(default)]
+    pub win: Option<bool>,
+    #[serde(default)]
+    pub team_pts: Option<i32>,
+    #[serde(default)]
+    pub opp_pts: Option<i32>,
+    pub adj_o: Option<f64>,
+    pub adj_d: Option<f64>,
+    pub off_ppp: Option<f64>,
+    pub off_efg: Option<f64>,
+    pub off_to: Option<f64>,
+    pub off_or: Option<f64>,
+    pub off_ftr: Option<f64>,
+    pub def_ppp: Option<f64>,
+    pub def_efg: Option<f64>,
+    pub def_to: Option<f64>,
+    pub def_or: Option<f64>,
+    pub def_ftr: Option<f64>,
+    pub game_score: Option<f64>,
+    pub opp_conf: Option<String>,
+    pub tempo: Option<f64>,
+    pub game_id: Option<String>,
+}
+
+/// Column layout of `results.csv` (no header row). Trailing columns beyond these are ignored.
+const RESULTS_HEADERS: [&str; 24] = [
+    "date", "game_type", "team", "conf", "opponent", "location", "result", // 0 - 6
+    "adj_o", "adj_d", "off_ppp", "off_efg", "off_to", "off_or", "off_ftr", // 7 - 13
+    "def_ppp", "def_efg", "def_to", "def_or", "def_ftr", // 14 - 18
+    "game_score", "opp_conf", "season", "tempo", "game_id", // 19 - 23
+];
+
+/// Normalizes the feed's dates (`11/4/24`, `11/4/2024`, `2024-11-04` or `20241104`) to YYYYMMDD.
+fn normalize_date(date: &str) -> Option<String> {
+    ["%m/%d/%y", "%m/%d/%Y", "%Y-%m-%d", "%Y%m%d"]
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(date, fmt).ok())
+        .map(|d| d.format("%Y%m%d").to_string())
+}
+
+/// Splits a result such as `W, 78-70` or `L, 61-64 (OT)` into (win, team points, opponent points).
+fn parse_result(result: &str) -> (Option<bool>, Option<i32>, Option<i32>) {
+    let win = match result.trim_start().chars().next() {
+        Some('W') => Some(true),
+        Some('L') => Some(false),
+        _ => None,
+    };
+
+    let score = result
+        .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .find(|part| part.contains('-') && part.len() > 2);
+    let (team_pts, opp_pts) = match score.and_then(|s| s.split_once('-')) {
+        Some((a, b)) => (a.parse().ok(), b.parse().ok()),
+        None => (None, None),
+    };
+
+    (win, team_pts, opp_pts)
+}
+
+/// Parses every team's game results for a single season, checking every row against
+/// `RESULTS_HEADERS`.
+pub fn parse_team_results_data(data: &[u8], year: i32) -> Result<(Vec<TeamGameResult>, IngestReport), Box<dyn Error>> {
+    info!("Parsing team results CSV...");
+
+    let headers = StringRecord::from(RESULTS_HEADERS.to_vec());
+    let mut drift = DriftDetector::new(
+        "team results",
+        &RESULTS_HEADERS,
+        &["team", "opponent"],
+        &["date", "game_type", "conf", "location", "result", "opp_conf", "game_id"],
+    )
+    .allow_extra_columns();
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let mut results: Vec<TeamGameResult> = Vec::new();
+    let mut report = IngestReport::new("team results");
+
+    for (i, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                report.reject(i, String::new(), e.to_string());
+                continue;
+            }
+        };
+        drift.check_csv_record(&record);
+
+        let parsed = record
+            .deserialize::<TeamGameResult>(Some(&headers))
+            .map_err(|e| e.to_string())
+            .and_then(|mut r| {
+                r.numdate = normalize_date(&r.date).ok_or_else(|| format!("Unrecognized date '{}'", r.date))?;
+                Ok(r)
+            });
+
+        match parsed {
+            Ok(mut r) => {
+                r.year = year;
+                (r.win, r.team_pts, r.opp_pts) = parse_result(r.result.as_deref().unwrap_or_default());
+                results.push(r);
+                report.accept();
+            }
+            Err(e) => report.reject(i, csv_raw(&record), e),
+        }
+    }
+
+    report.log_summary();
+
+    Ok((results, report.with_drift(drift.report())))
+}
+
+pub async fn insert_team_results(
+    session: &Session,
+    results: &[TeamGameResult],
+) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO team_results (
+        team, year, numdate, date, game_type, conf, opponent, location, result,
+        win, team_pts, opp_pts, adj_o, adj_d,
+        off_ppp, off_efg, off_to, off_or, off_ftr,
+        def_ppp, def_efg, def_to, def_or, def_ftr,
+        game_score, opp_conf, tempo, game_id
+    ) VALUES (
+        ?, ?, ?, ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?,
+        ?, ?, ?, ?
+    )
+"#;
+
+    write_batched(session, "team_results", query, results, |r| (r.team.clone(), r.year)).await
+}
+
+/// Fetches a team's game log for a season, oldest game first.
+pub async fn get_team_results_from_db(
+    session: &Session,
+    team: &str,
+    year: i32,
+) -> Result<Vec<TeamGameResult>, QueryError> {
+    let query = r#"
+    SELECT team, year, numdate, date, game_type, conf, opponent, location, result,
+           win, team_pts, opp_pts, adj_o, adj_d,
+           off_ppp, off_efg, off_to, off_or, off_ftr,
+           def_ppp, def_efg, def_to, def_or, def_ftr,
+           game_score, opp_conf, tempo, game_id
+    FROM team_results WHERE team = ? AND year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (team, year)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut results = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match TeamGameResult::from_row(row) {
+            Ok(r) => results.push(r),
+            Err(e) => error!("Team result row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn normalizes_every_date_format() {
+        for date in ["11/4/24", "11/04/24", "11/4/2024", "2024-11-04", "20241104"] {
+            assert_eq!(normalize_date(date).as_deref(), Some("20241104"), "{}", date);
+        }
+        for date in ["", "Nov 4", "2024/11/04", "13/4/24", "11/31/2024"] {
+            assert_eq!(normalize_date(date), None, "{}", date);
+        }
+    }
+
+    #[test]
+    fn splits_results_into_outcome_and_score() {
+        assert_eq!(parse_result("W, 78-70"), (Some(true), Some(78), Some(70)));
+        assert_eq!(parse_result("L, 61-64 (OT)"), (Some(false), Some(61), Some(64)));
+        assert_eq!(parse_result("L, 61-64 (2OT)"), (Some(false), Some(61), Some(64)));
+        assert_eq!(parse_result(""), (None, None, None));
+        assert_eq!(parse_result("Postponed"), (None, None, None));
+        assert_eq!(parse_result("W, forfeit"), (Some(true), None, None));
+    }
+
+    #[test]
+    fn parses_the_results_fixture() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot/2026_results.csv");
+        let (results, report) = parse_team_results_data(&std::fs::read(path).unwrap(), 2026).unwrap();
+
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(|r| r.year == 2026));
+        let numdates: Vec<&str> = results.iter().map(|r| r.numdate.as_str()).collect();
+        assert_eq!(numdates, ["20251110", "20251117", "20251124", "20251201", "20251208"]);
+
+        let unc = &results[0];
+        assert_eq!((unc.team.as_str(), unc.opponent.as_str()), ("North Carolina", "Kansas"));
+        assert_eq!((unc.win, unc.team_pts, unc.opp_pts), (Some(true), Some(78), Some(70)));
+        assert_eq!((unc.adj_o, unc.adj_d, unc.tempo), (Some(115.2), Some(95.1), Some(70.1)));
+        assert_eq!(unc.game_id.as_deref(), Some("North Carolina-Kansas-20251110"));
+        assert_eq!((results[1].win, results[1].team_pts, results[1].opp_pts), (Some(false), Some(61), Some(64)));
+        assert_eq!((results[2].win, results[2].team_pts, results[2].opp_pts), (Some(false), Some(61), Some(64)));
+
+        // Unplayed and postponed games are kept, just without a result or ratings.
+        for r in &results[3..] {
+            assert_eq!((r.win, r.team_pts, r.opp_pts, r.adj_o), (None, None, None, None), "{:?}", r.game_id);
+        }
+
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].index, 5);
+        assert!(report.rejected[0].reason.contains("Dec 8"), "{}", report.rejected[0].reason);
+    }
+}
@@ -0,0 +1,211 @@
+// src/get_team_shooting.rs
+use std::error::Error;
+use log::error;
+use serde::{Deserialize, Serialize};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::get_player_shots::{get_opt_count, ratio, total_attempts};
+use crate::ingest_report::{IngestReport, json_raw};
+use crate::schema_drift::DriftDetector;
+
+/// A team's shooting by distance from `pbp_teamsstats.json`, on offense (`off_`) and what it
+/// allowed on defense (`def_`). `_share` is the zone's fraction of all field goal attempts.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct TeamShooting {
+    pub team: String,
+    pub year: i32,
+    pub off_rim_made: Option<i32>,
+    pub off_rim_att: Option<i32>,
+    pub off_mid_made: Option<i32>,
+    pub off_mid_att: Option<i32>,
+    pub off_three_made: Option<i32>,
+    pub off_three_att: Option<i32>,
+    pub def_rim_made: Option<i32>,
+    pub def_rim_att: Option<i32>,
+    pub def_mid_made: Option<i32>,
+    pub def_mid_att: Option<i32>,
+    pub def_three_made: Option<i32>,
+    pub def_three_att: Option<i32>,
+    pub off_rim_pct: Option<f64>,
+    pub off_rim_share: Option<f64>,
+    pub off_mid_pct: Option<f64>,
+    pub off_mid_share: Option<f64>,
+    pub off_three_pct: Option<f64>,
+    pub off_three_share: Option<f64>,
+    pub def_rim_pct: Option<f64>,
+    pub def_rim_share: Option<f64>,
+    pub def_mid_pct: Option<f64>,
+    pub def_mid_share: Option<f64>,
+    pub def_three_pct: Option<f64>,
+    pub def_three_share: Option<f64>,
+}
+
+/// Positional layout of a row in `pbp_teamsstats.json`.
+const TEAM_SHOOTING_COLUMNS: [&str; 13] = [
+    "team",
+    "off_rim_made", "off_rim_att", "off_mid_made", "off_mid_att", "off_three_made", "off_three_att",
+    "def_rim_made", "def_rim_att", "def_mid_made", "def_mid_att", "def_three_made", "def_three_att",
+];
+
+impl TeamShooting {
+    /// Parses one team row. Layout:
+    /// `[team, off_rim_made, off_rim_att, off_mid_made, off_mid_att, off_three_made, off_three_att,
+    ///   def_rim_made, def_rim_att, def_mid_made, def_mid_att, def_three_made, def_three_att]`
+    pub fn from_json_array(arr: &[serde_json::Value], year: i32) -> Result<Self, Box<dyn Error>> {
+        let team = arr.first()
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or("Missing or invalid string at index 0")?;
+
+        let count = |idx: usize| get_opt_count(arr.get(idx).unwrap_or(&serde_json::Value::Null));
+
+        let off_rim_made = count(1)?;
+        let off_rim_att = count(2)?;
+        let off_mid_made = count(3)?;
+        let off_mid_att = count(4)?;
+        let off_three_made = count(5)?;
+        let off_three_att = count(6)?;
+        let def_rim_made = count(7)?;
+        let def_rim_att = count(8)?;
+        let def_mid_made = count(9)?;
+        let def_mid_att = count(10)?;
+        let def_three_made = count(11)?;
+        let def_three_att = count(12)?;
+
+        let off_att = total_attempts(&[off_rim_att, off_mid_att, off_three_att])?;
+        let def_att = total_attempts(&[def_rim_att, def_mid_att, def_three_att])?;
+
+        Ok(TeamShooting {
+            team,
+            year,
+            off_rim_made,
+            off_rim_att,
+            off_mid_made,
+            off_mid_att,
+            off_three_made,
+            off_three_att,
+            def_rim_made,
+            def_rim_att,
+            def_mid_made,
+            def_mid_att,
+            def_three_made,
+            def_three_att,
+            off_rim_pct: ratio(off_rim_made, off_rim_att),
+            off_rim_share: ratio(off_rim_att, off_att),
+            off_mid_pct: ratio(off_mid_made, off_mid_att),
+            off_mid_share: ratio(off_mid_att, off_att),
+            off_three_pct: ratio(off_three_made, off_three_att),
+            off_three_share: ratio(off_three_att, off_att),
+            def_rim_pct: ratio(def_rim_made, def_rim_att),
+            def_rim_share: ratio(def_rim_att, def_att),
+            def_mid_pct: ratio(def_mid_made, def_mid_att),
+            def_mid_share: ratio(def_mid_att, def_att),
+            def_three_pct: ratio(def_three_made, def_three_att),
+            def_three_share: ratio(def_three_att, def_att),
+        })
+    }
+}
+
+/// Parses every team's shooting splits for a single season.
+pub fn parse_team_shooting_data(data: &[u8], year: i32) -> Result<(Vec<TeamShooting>, IngestReport), Box<dyn Error>> {
+    let raw_data: Vec<Vec<serde_json::Value>> = serde_json::from_slice(data)?;
+
+    let mut drift = DriftDetector::new("team shooting", &TEAM_SHOOTING_COLUMNS, &["team"], &[]);
+    let mut teams: Vec<TeamShooting> = Vec::new();
+    let mut report = IngestReport::new("team shooting");
+
+    for (i, row) in raw_data.into_iter().enumerate() {
+        drift.check_json_row(&row);
+        match TeamShooting::from_json_array(&row, year) {
+            Ok(record) => {
+                teams.push(record);
+                report.accept();
+            }
+            Err(e) => report.reject(i, json_raw(&row), e.to_string()),
+        }
+    }
+
+    report.log_summary();
+
+    Ok((teams, report.with_drift(drift.report())))
+}
+
+pub async fn insert_team_shooting(
+    session: &Session,
+    teams: &[TeamShooting],
+) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO team_shooting (
+        team, year,
+        off_rim_made, off_rim_att, off_mid_made, off_mid_att, off_three_made, off_three_att,
+        def_rim_made, def_rim_att, def_mid_made, def_mid_att, def_three_made, def_three_att,
+        off_rim_pct, off_rim_share, off_mid_pct, off_mid_share, off_three_pct, off_three_share,
+        def_rim_pct, def_rim_share, def_mid_pct, def_mid_share, def_three_pct, def_three_share
+    ) VALUES (
+        ?, ?,
+        ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?,
+        ?, ?, ?, ?, ?, ?
+    )
+"#;
+
+    write_batched(session, "team_shooting", query, teams, |t| t.year).await
+}
+
+/// Fetches every team's shooting splits for a season.
+pub async fn get_team_shooting_from_db(
+    session: &Session,
+    year: i32,
+) -> Result<Vec<TeamShooting>, QueryError> {
+    let query = r#"
+    SELECT team, year,
+           off_rim_made, off_rim_att, off_mid_made, off_mid_att, off_three_made, off_three_att,
+           def_rim_made, def_rim_att, def_mid_made, def_mid_att, def_three_made, def_three_att,
+           off_rim_pct, off_rim_share, off_mid_pct, off_mid_share, off_three_pct, off_three_share,
+           def_rim_pct, def_rim_share, def_mid_pct, def_mid_share, def_three_pct, def_three_share
+    FROM team_shooting WHERE year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (year,)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut teams = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match TeamShooting::from_row(row) {
+            Ok(t) => teams.push(t),
+            Err(e) => error!("Team shooting row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(teams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_attempt_totals_past_i32_max() {
+        let near_max = i32::MAX - 10;
+        let data = json!([
+            ["Duke", 0, near_max, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0],
+            ["Duke", 0, 0, 0, 0, 0, 0, 0, near_max, 0, 0, 0, 11],
+            ["North Carolina", 0, near_max, 0, 10, 0, 0, 300, 600, 100, 250, 50, 150],
+        ]);
+        let (teams, report) = parse_team_shooting_data(data.to_string().as_bytes(), 2026).unwrap();
+
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].index, 1);
+        assert_eq!(teams.len(), 2);
+
+        let unc = &teams[1];
+        assert_eq!(unc.off_rim_share, Some(near_max as f64 / (i32::MAX as f64)));
+        assert_eq!(unc.def_rim_pct, Some(0.5));
+        assert_eq!(unc.def_rim_share, Some(600.0 / 1000.0));
+        assert_eq!(unc.def_three_share, Some(150.0 / 1000.0));
+    }
+}
@@ -1,12 +1,19 @@
 use std::error::Error;
-use log::info;
+use log::error;
 use serde::{Deserialize, Serialize};
 use scylla::{Session, SerializeRow, FromRow}; // FromRow is already here
 
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::ingest_report::IngestReport;
+use crate::schema_drift::DriftDetector;
+
 #[derive(Debug, Clone, Serialize, Deserialize, SerializeRow, FromRow)]
 pub struct TeamStats {
     pub rank: i32,
     pub team: String,
+    // Not part of team_results.json; filled in from the requested season.
+    #[serde(default)]
+    pub year: i32,
     pub conf: String,
     pub record: String,
     pub adjoe: f64,
@@ -52,21 +59,63 @@ pub struct TeamStats {
     pub adj_tempo: f64,
 }
 
-pub async fn get_team_stats() -> Result<Vec<TeamStats>, Box<dyn Error>> {
-    let url = "https://barttorvik.com/2026_team_results.json";
-    let response = reqwest::get(url).await?.json::<Vec<TeamStats>>().await?;
-    info!("Fetched {} records", response.len());
+/// Positional layout of a row in `team_results.json` (every `TeamStats` field but `year`).
+const TEAM_STATS_COLUMNS: [&str; 45] = [
+    "rank", "team", "conf", "record", "adjoe", "adjoe_rank", "adjde", "adjde_rank", "barthag", "barthag_rank",
+    "proj_wins", "proj_losses", "proj_conf_wins", "proj_conf_losses", "conf_record",
+    "sos", "nconf_sos", "conf_sos", "proj_sos", "proj_nconf_sos", "proj_conf_sos",
+    "elite_sos", "elite_ncsos", "opp_adjoe", "opp_adjde", "opp_proj_adjoe", "opp_proj_adjde",
+    "conf_adjoe", "conf_adjde", "qual_adjoe", "qual_adjde", "qual_barthag", "qual_games",
+    "fun", "conf_pf", "conf_pa", "conf_poss", "conf_adj_o", "conf_adj_d", "conf_sos_remain",
+    "conf_win_perc", "wab", "wab_rank", "fun_rank", "adj_tempo",
+];
+
+// Deserializes a positional row by `TEAM_STATS_COLUMNS` name, so `year` (not in the feed)
+// takes its default here yet still round-trips through stores that keep rows as JSON.
+fn team_stats_row(row: &serde_json::Value) -> Result<TeamStats, String> {
+    let Some(arr) = row.as_array() else {
+        return TeamStats::deserialize(row).map_err(|e| e.to_string());
+    };
+    if arr.len() != TEAM_STATS_COLUMNS.len() {
+        return Err(format!("Expected {} columns, found {}", TEAM_STATS_COLUMNS.len(), arr.len()));
+    }
+    let fields: serde_json::Map<String, serde_json::Value> =
+        TEAM_STATS_COLUMNS.iter().map(|c| c.to_string()).zip(arr.iter().cloned()).collect();
+    TeamStats::deserialize(serde_json::Value::Object(fields)).map_err(|e| e.to_string())
+}
+
+/// Parses the team results page data for a single season and tags each row with `year`.
+/// Every row is checked against `TEAM_STATS_COLUMNS`; rows that fail to deserialize are skipped.
+pub fn parse_team_stats(data: &[u8], year: i32) -> Result<(Vec<TeamStats>, IngestReport), Box<dyn Error>> {
+    let raw_data: Vec<serde_json::Value> = serde_json::from_slice(data)?;
 
-    Ok(response)
+    let mut drift = DriftDetector::new("team stats", &TEAM_STATS_COLUMNS, &["team", "conf", "record", "conf_record"], &[]);
+    let mut response: Vec<TeamStats> = Vec::new();
+    let mut report = IngestReport::new("team stats");
+
+    for (i, row) in raw_data.iter().enumerate() {
+        drift.check_json_value(row);
+        match team_stats_row(row) {
+            Ok(mut team) => {
+                team.year = year;
+                response.push(team);
+                report.accept();
+            }
+            Err(e) => report.reject(i, row.to_string(), e),
+        }
+    }
+    report.log_summary();
+
+    Ok((response, report.with_drift(drift.report())))
 }
 
 pub async fn insert_team_stats(
     session: &Session,
     teams: &[TeamStats],
-) -> Result<(), scylla::transport::errors::QueryError> {
+) -> Result<WriteReport, scylla::transport::errors::QueryError> {
     let query = r#"
-        INSERT INTO stats.team_stats (
-            rank, team, conf, record, adjoe, adjoe_rank, adjde, adjde_rank, barthag, barthag_rank,
+        INSERT INTO team_stats (
+            rank, team, year, conf, record, adjoe, adjoe_rank, adjde, adjde_rank, barthag, barthag_rank,
             proj_wins, proj_losses, proj_conf_wins, proj_conf_losses, conf_record,
             sos, nconf_sos, conf_sos, proj_sos, proj_nconf_sos, proj_conf_sos,
             elite_sos, elite_ncsos, opp_adjoe, opp_adjde, opp_proj_adjoe, opp_proj_adjde,
@@ -74,18 +123,66 @@ pub async fn insert_team_stats(
             fun, conf_pf, conf_pa, conf_poss, conf_adj_o, conf_adj_d, conf_sos_remain,
             conf_win_perc, wab, wab_rank, fun_rank, adj_tempo
         ) VALUES (
-            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
         );
     "#;
 
+    write_batched(session, "team_stats", query, teams, |team| team.year).await
+}
+
+pub async fn get_team_stats_from_db(
+    session: &Session,
+    year: i32,
+) -> Result<Vec<TeamStats>, scylla::transport::errors::QueryError> {
+    let query = r#"
+        SELECT rank, team, year, conf, record, adjoe, adjoe_rank, adjde, adjde_rank, barthag, barthag_rank,
+               proj_wins, proj_losses, proj_conf_wins, proj_conf_losses, conf_record,
+               sos, nconf_sos, conf_sos, proj_sos, proj_nconf_sos, proj_conf_sos,
+               elite_sos, elite_ncsos, opp_adjoe, opp_adjde, opp_proj_adjoe, opp_proj_adjde,
+               conf_adjoe, conf_adjde, qual_adjoe, qual_adjde, qual_barthag, qual_games,
+               fun, conf_pf, conf_pa, conf_poss, conf_adj_o, conf_adj_d, conf_sos_remain,
+               conf_win_perc, wab, wab_rank, fun_rank, adj_tempo
+        FROM team_stats WHERE year = ?
+    "#;
+
     let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (year,)).await?;
+    let rows = result.rows.unwrap_or_default();
 
-    for team in teams {
-        session.execute(&prepared, &team).await?;
+    let mut stats = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match TeamStats::from_row(row) {
+            Ok(stat) => stats.push(stat),
+            Err(e) => error!("Failed to parse row {}: {}", i, e),
+        }
     }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/snapshot/2026_team_results.json");
 
-    Ok(())
+    #[test]
+    fn object_rows_reach_the_drift_detector() {
+        let mut rows: Vec<serde_json::Value> = serde_json::from_str(FIXTURE).unwrap();
+        let mut fields: serde_json::Map<String, serde_json::Value> =
+            TEAM_STATS_COLUMNS.iter().map(|c| c.to_string()).zip(rows[0].as_array().unwrap().iter().cloned()).collect();
+        fields.insert("adjoe".to_string(), serde_json::json!("n/a"));
+        rows[0] = serde_json::Value::Object(fields.clone());
+        fields.remove("adjoe");
+        fields.remove("adjde");
+        rows[1] = serde_json::Value::Object(fields);
+
+        let (teams, report) = parse_team_stats(&serde_json::to_vec(&rows).unwrap(), 2026).unwrap();
+        assert_eq!(teams.len(), 3);
+        let drift = report.drift.unwrap();
+        assert_eq!((drift.rows_checked, drift.drifted_rows), (5, 2));
+        assert_eq!(drift.widths_seen.get(&(TEAM_STATS_COLUMNS.len() - 2)), Some(&1));
+    }
 }
@@ -0,0 +1,398 @@
+// src/http_client.rs
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::{info, warn};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+
+use crate::feed_archive::sha256_hex;
+
+const USER_AGENT: &str = concat!("mater/", env!("CARGO_PKG_VERSION"), " (college basketball analytics)");
+const CACHE_INDEX_FILE: &str = "index.json";
+// Longest we ever wait between retries, whatever the attempt count or Retry-After says.
+const MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+/// How the shared fetch client behaves.
+#[derive(Debug, Clone)]
+pub struct FetchConfig {
+    /// Total time allowed for one request, including reading the body.
+    pub timeout: Duration,
+    /// Retries after the first attempt for timeouts, connection errors, 429s and 5xxs.
+    pub max_retries: u32,
+    /// First retry delay; doubled on every further retry.
+    pub base_backoff: Duration,
+    /// Minimum time between the start of two requests.
+    pub min_interval: Duration,
+    /// Where downloads and their `ETag`/`Last-Modified` validators are cached;
+    /// `None` turns conditional requests off.
+    pub cache_dir: Option<PathBuf>,
+}
+
+// Validators for one cached URL; the body is stored next to the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body_file: String,
+}
+
+enum Attempt {
+    Fetched(Vec<u8>),
+    Retry(String, Option<Duration>),
+    // A 304 for a copy no longer on disk; its validators are dropped so the next request
+    // is unconditional.
+    Unconditional,
+}
+
+// How long to wait before the retry after `retries` earlier ones: the server's Retry-After if
+// it sent one, otherwise `base_backoff` doubled per retry, never more than `MAX_BACKOFF`.
+fn retry_delay(base_backoff: Duration, retries: u32, retry_after: Option<Duration>) -> Duration {
+    let backoff = base_backoff.saturating_mul(2u32.saturating_pow(retries));
+    retry_after.unwrap_or(backoff).min(MAX_BACKOFF)
+}
+
+/// The one HTTP client every feed download goes through: timeouts, a User-Agent, retries
+/// with exponential backoff, request spacing, and conditional GETs against cached copies.
+pub struct FetchClient {
+    client: reqwest::Client,
+    config: FetchConfig,
+    last_request: tokio::sync::Mutex<Option<Instant>>,
+    cache: Mutex<BTreeMap<String, CacheEntry>>,
+}
+
+impl FetchClient {
+    pub fn new(config: FetchConfig) -> Result<Self, Box<dyn Error>> {
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(config.timeout)
+            .connect_timeout(config.timeout.min(Duration::from_secs(30)))
+            .build()?;
+
+        let cache = match &config.cache_dir {
+            Some(dir) => {
+                fs::create_dir_all(dir)?;
+                let index = dir.join(CACHE_INDEX_FILE);
+                if index.exists() {
+                    serde_json::from_slice(&fs::read(&index)?).unwrap_or_else(|e| {
+                        warn!("Ignoring corrupt HTTP cache index {}: {}", index.display(), e);
+                        BTreeMap::new()
+                    })
+                } else {
+                    BTreeMap::new()
+                }
+            }
+            None => BTreeMap::new(),
+        };
+
+        Ok(FetchClient {
+            client,
+            config,
+            last_request: tokio::sync::Mutex::new(None),
+            cache: Mutex::new(cache),
+        })
+    }
+
+    /// Downloads `url`, retrying transient failures. If the server says the content is
+    /// unchanged (304), the cached copy from the previous download is returned instead.
+    pub async fn get(&self, url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut retries = 0;
+        loop {
+            self.wait_turn().await;
+            let attempt = self.attempt(url).await?;
+            match attempt {
+                Attempt::Fetched(body) => return Ok(body),
+                Attempt::Unconditional => continue,
+                Attempt::Retry(reason, _) if retries >= self.config.max_retries => {
+                    return Err(format!("GET {} failed after {} attempts: {}", url, retries + 1, reason).into());
+                }
+                Attempt::Retry(reason, retry_after) => {
+                    let delay = retry_delay(self.config.base_backoff, retries, retry_after);
+                    retries += 1;
+                    warn!("GET {} failed ({}); retry {}/{} in {:?}", url, reason, retries, self.config.max_retries, delay);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    async fn attempt(&self, url: &str) -> Result<Attempt, Box<dyn Error>> {
+        let cached = self.cached(url);
+
+        let mut request = self.client.get(url);
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match request.send().await {
+            Ok(r) => r,
+            Err(e) if e.is_timeout() || e.is_connect() => return Ok(Attempt::Retry(e.to_string(), None)),
+            Err(e) => return Err(e.into()),
+        };
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED
+            && let (Some(entry), Some(dir)) = (&cached, &self.config.cache_dir)
+        {
+            match fs::read(dir.join(&entry.body_file)) {
+                Ok(body) => {
+                    info!("{} not modified since the last download; using the cached copy", url);
+                    return Ok(Attempt::Fetched(body));
+                }
+                Err(e) => {
+                    warn!("{} not modified, but its cached copy can't be read ({}); downloading it again", url, e);
+                    self.forget(url)?;
+                    return Ok(Attempt::Unconditional);
+                }
+            }
+        }
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Ok(Attempt::Retry(status.to_string(), retry_after));
+        }
+        if !status.is_success() {
+            return Err(format!("GET {} failed: {}", url, status).into());
+        }
+
+        let header = |name: HeaderName| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+
+        let body = match response.bytes().await {
+            Ok(b) => b.to_vec(),
+            Err(e) if e.is_timeout() || e.is_body() => return Ok(Attempt::Retry(e.to_string(), None)),
+            Err(e) => return Err(e.into()),
+        };
+
+        if etag.is_some() || last_modified.is_some() {
+            self.store(url, etag, last_modified, &body)?;
+        }
+        Ok(Attempt::Fetched(body))
+    }
+
+    // Spaces requests out by at least `min_interval`.
+    async fn wait_turn(&self) {
+        let mut last = self.last_request.lock().await;
+        if let Some(at) = *last {
+            let elapsed = at.elapsed();
+            if elapsed < self.config.min_interval {
+                tokio::time::sleep(self.config.min_interval - elapsed).await;
+            }
+        }
+        *last = Some(Instant::now());
+    }
+
+    // The cache entry for `url`, if its body is still on disk.
+    fn cached(&self, url: &str) -> Option<CacheEntry> {
+        let dir = self.config.cache_dir.as_ref()?;
+        let entry = self.cache.lock().unwrap().get(url).cloned()?;
+        dir.join(&entry.body_file).is_file().then_some(entry)
+    }
+
+    fn forget(&self, url: &str) -> Result<(), Box<dyn Error>> {
+        let Some(dir) = &self.config.cache_dir else { return Ok(()) };
+
+        let mut cache = self.cache.lock().unwrap();
+        if cache.remove(url).is_some() {
+            fs::write(dir.join(CACHE_INDEX_FILE), serde_json::to_vec_pretty(&*cache)?)?;
+        }
+        Ok(())
+    }
+
+    fn store(&self, url: &str, etag: Option<String>, last_modified: Option<String>, body: &[u8]) -> Result<(), Box<dyn Error>> {
+        let Some(dir) = &self.config.cache_dir else { return Ok(()) };
+
+        let body_file = sha256_hex(url.as_bytes());
+        fs::write(dir.join(&body_file), body)?;
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.insert(url.to_string(), CacheEntry { etag, last_modified, body_file });
+        fs::write(dir.join(CACHE_INDEX_FILE), serde_json::to_vec_pretty(&*cache)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn config(max_retries: u32, base_backoff: Duration, cache_dir: Option<PathBuf>) -> FetchConfig {
+        FetchConfig {
+            timeout: Duration::from_secs(5),
+            max_retries,
+            base_backoff,
+            min_interval: Duration::from_millis(0),
+            cache_dir,
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mater-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn response(status: &str, headers: &[&str], body: &str) -> String {
+        let headers: String = headers.iter().map(|h| format!("{}\r\n", h)).collect();
+        format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}", status, body.len(), headers, body)
+    }
+
+    // A local server answering the n-th request (from 0) with `respond(n, request)`. Returns
+    // the URL to fetch and every request it has received, lowercased.
+    async fn serve<F>(respond: F) -> (String, Arc<Mutex<Vec<String>>>)
+    where
+        F: Fn(usize, &str) -> String + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/2026_fffinal.csv", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+                while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut chunk).await.unwrap() {
+                        0 => break,
+                        n => buf.extend_from_slice(&chunk[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&buf).to_ascii_lowercase();
+                let n = {
+                    let mut seen = seen.lock().unwrap();
+                    seen.push(request.clone());
+                    seen.len() - 1
+                };
+                socket.write_all(respond(n, &request).as_bytes()).await.unwrap();
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, requests)
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let base = Duration::from_secs(1);
+        let delays: Vec<u64> = [0, 1, 2, 3, 6, 7, 40].iter().map(|&r| retry_delay(base, r, None).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 64, 120, 120]);
+
+        // Retry-After replaces the backoff, but is capped all the same.
+        assert_eq!(retry_delay(base, 5, Some(Duration::from_secs(3))), Duration::from_secs(3));
+        assert_eq!(retry_delay(base, 0, Some(Duration::from_secs(3600))), MAX_BACKOFF);
+        assert_eq!(retry_delay(Duration::ZERO, 10, None), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures() {
+        let (url, requests) = serve(|n, _| match n {
+            0 => response("503 Service Unavailable", &[], ""),
+            1 => response("500 Internal Server Error", &[], ""),
+            _ => response("200 OK", &[], "team,efg\n"),
+        })
+        .await;
+
+        let client = FetchClient::new(config(2, Duration::ZERO, None)).unwrap();
+        assert_eq!(client.get(&url).await.unwrap(), b"team,efg\n");
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (url, requests) = serve(|_, _| response("429 Too Many Requests", &[], "")).await;
+
+        let client = FetchClient::new(config(2, Duration::ZERO, None)).unwrap();
+        let error = client.get(&url).await.unwrap_err().to_string();
+        assert!(error.contains("failed after 3 attempts"), "{}", error);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // Client errors other than 429 are not retried.
+        let (url, requests) = serve(|_, _| response("404 Not Found", &[], "")).await;
+        assert!(client.get(&url).await.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_after_overrides_the_backoff() {
+        let (url, _) = serve(|n, _| match n {
+            0 => response("429 Too Many Requests", &["Retry-After: 0"], ""),
+            _ => response("200 OK", &[], "ok"),
+        })
+        .await;
+
+        // With a minute of backoff, only the server's Retry-After lets this finish in time.
+        let client = FetchClient::new(config(1, Duration::from_secs(60), None)).unwrap();
+        let body = tokio::time::timeout(Duration::from_secs(10), client.get(&url)).await.unwrap().unwrap();
+        assert_eq!(body, b"ok");
+    }
+
+    #[tokio::test]
+    async fn not_modified_returns_the_cached_copy() {
+        let cache_dir = scratch_dir("http-cache-hit");
+        let (url, requests) = serve(|n, request| match n {
+            0 => response("200 OK", &["ETag: \"v1\""], "team,efg\n"),
+            _ if request.contains("if-none-match: \"v1\"") => response("304 Not Modified", &[], ""),
+            _ => response("200 OK", &[], "unconditional"),
+        })
+        .await;
+
+        let client = FetchClient::new(config(0, Duration::ZERO, Some(cache_dir.clone()))).unwrap();
+        assert_eq!(client.get(&url).await.unwrap(), b"team,efg\n");
+        // A new client picks the validators back up from the index on disk.
+        let client = FetchClient::new(config(0, Duration::ZERO, Some(cache_dir.clone()))).unwrap();
+        assert_eq!(client.get(&url).await.unwrap(), b"team,efg\n");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn not_modified_without_a_readable_copy_downloads_again() {
+        let cache_dir = scratch_dir("http-cache-lost");
+        let dir = cache_dir.clone();
+        let (url, requests) = serve(move |n, request| match n {
+            0 => response("200 OK", &["ETag: \"v1\""], "team,efg\n"),
+            1 => {
+                // The cached body disappears between the client's check and the 304.
+                for entry in fs::read_dir(&dir).unwrap() {
+                    let path = entry.unwrap().path();
+                    if path.file_name().unwrap() != CACHE_INDEX_FILE {
+                        fs::remove_file(path).unwrap();
+                    }
+                }
+                assert!(request.contains("if-none-match"));
+                response("304 Not Modified", &[], "")
+            }
+            _ => response("200 OK", &[], "team,efg,ftr\n"),
+        })
+        .await;
+
+        let client = FetchClient::new(config(0, Duration::ZERO, Some(cache_dir.clone()))).unwrap();
+        assert_eq!(client.get(&url).await.unwrap(), b"team,efg\n");
+        assert_eq!(client.get(&url).await.unwrap(), b"team,efg,ftr\n");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(!requests[2].contains("if-none-match"), "{}", requests[2]);
+        assert!(client.cache.lock().unwrap().is_empty());
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
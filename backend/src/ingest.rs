@@ -0,0 +1,549 @@
+// src/ingest.rs
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::path::PathBuf;
+use chrono::Utc;
+use log::{info, error};
+
+use crate::batch_writer::WriteReport;
+use crate::cli::Source;
+use crate::feed_archive::FeedArchive;
+use crate::feed_source::{FeedResolver, decompress};
+use crate::http_client::{FetchClient, FetchConfig};
+use crate::ingest_report::{IngestReport, IngestRun};
+use crate::stats_store::StatsStore;
+use crate::player_registry::player_records;
+use crate::team_registry::TeamRegistry;
+use crate::transfers::refresh_transfers;
+use crate::conferences::refresh_conferences;
+use crate::rating_history::{player_snapshots, snapshot_date, team_snapshots};
+use crate::get_player_stats::parse_player_data;
+use crate::get_team_stats::parse_team_stats;
+use crate::get_game_stats::{GameStats, parse_game_data, diff_games};
+use crate::analytics_calculator::recompute_player_analytics;
+use crate::get_schedule::parse_schedule_data;
+use crate::get_four_factors::parse_four_factors_data;
+use crate::get_player_shots::parse_player_shot_data;
+use crate::get_team_shooting::parse_team_shooting_data;
+use crate::get_team_results::parse_team_results_data;
+
+/// What an ingest run loads and where from.
+#[derive(Debug, Clone)]
+pub struct IngestOptions {
+    pub seasons: Vec<i32>,
+    pub sources: Vec<Source>,
+    pub feeds: FeedResolver,
+    /// Where raw feeds are archived; `None` disables archiving and change detection.
+    pub archive_dir: Option<PathBuf>,
+    /// Parse and write feeds even when their content is unchanged since the last load.
+    pub force: bool,
+    /// Largest fraction of a feed's rows allowed to drift from the expected layout
+    /// before the feed is rejected without writing anything.
+    pub max_drift: f64,
+    pub fetch: FetchConfig,
+}
+
+/// Loads every requested feed for every requested season, recording each feed's outcome
+/// in `ingest_runs` and its rejected rows in `ingest_rejected_rows`.
+///
+/// A failing feed is logged and skipped so one missing file (older seasons lack the
+/// play-by-play feeds) doesn't abort a long backfill; the run still returns an error
+/// listing every feed that failed.
+pub async fn run_ingest(store: &dyn StatsStore, options: &IngestOptions) -> Result<(), Box<dyn Error>> {
+    let client = FetchClient::new(options.fetch.clone())?;
+    let mut archive = options.archive_dir.as_deref().map(|dir| FeedArchive::open(dir, &store.name())).transpose()?;
+    let mut failures: Vec<String> = Vec::new();
+    let mut writes: Vec<WriteReport> = Vec::new();
+    let mut teams = TeamRegistry::load(store).await?;
+    // Seasons whose players feed was written; their transfers (in and out) are rebuilt below.
+    let mut transfer_seasons: BTreeSet<i32> = BTreeSet::new();
+    // Seasons whose teams feed was written; their conference tables are rebuilt below.
+    let mut conference_seasons: Vec<i32> = Vec::new();
+
+    for &season in &options.seasons {
+        info!("Ingesting season {} ({:?})", season, options.sources);
+        for &source in &options.sources {
+            let mut run = IngestRun::start(&source.to_string(), season);
+            match ingest_source(store, options, &client, archive.as_mut(), &mut teams, season, source, &mut run).await {
+                Ok((status, written)) => {
+                    if source == Source::Players && written.is_some() {
+                        transfer_seasons.extend([season, season + 1]);
+                    }
+                    if source == Source::Teams && written.is_some() {
+                        conference_seasons.push(season);
+                    }
+                    writes.extend(written);
+                    run.finish(status, None);
+                }
+                Err(e) => {
+                    error!("Failed to ingest {} for {}: {}", source, season, e);
+                    failures.push(format!("{} {}", source, season));
+                    run.finish("failed", Some(e.to_string()));
+                }
+            }
+            if let Err(e) = store.write_ingest_run(&run).await {
+                error!("Failed to record ingest run for {} {}: {}", source, season, e);
+            }
+        }
+    }
+
+    // Teams first seen (or spelled differently) in this run, and conference changes.
+    let changed_teams = teams.changed();
+    if !changed_teams.is_empty() {
+        match store.write_teams(&changed_teams).await {
+            Ok(written) => writes.push(written),
+            Err(e) => {
+                error!("Failed to update the team registry: {}", e);
+                failures.push("team registry".to_string());
+            }
+        }
+    }
+
+    if !transfer_seasons.is_empty() {
+        let seasons: Vec<i32> = transfer_seasons.into_iter().collect();
+        if let Err(e) = refresh_transfers(store, Some(&seasons)).await {
+            error!("Failed to rebuild transfers for {:?}: {}", seasons, e);
+            failures.push("transfers".to_string());
+        }
+    }
+
+    if !conference_seasons.is_empty() && let Err(e) = refresh_conferences(store, Some(&conference_seasons)).await {
+        error!("Failed to rebuild conference tables for {:?}: {}", conference_seasons, e);
+        failures.push("conferences".to_string());
+    }
+
+    log_write_summary(&writes);
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} feed(s) failed: {}", failures.len(), failures.join(", ")).into())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn ingest_source(
+    store: &dyn StatsStore,
+    options: &IngestOptions,
+    client: &FetchClient,
+    archive: Option<&mut FeedArchive>,
+    teams: &mut TeamRegistry,
+    season: i32,
+    source: Source,
+    run: &mut IngestRun,
+) -> Result<(&'static str, Option<WriteReport>), Box<dyn Error>> {
+    let location = options.feeds.locate(source, season);
+    let file_name = source.file_name(season);
+    run.location = Some(location.to_string());
+    info!("Fetching {} for {} from: {}", source, season, location);
+    let raw = location.read_raw(client).await?;
+
+    // Archive the bytes as fetched, and skip the parse when they match the last load.
+    let archived = match archive {
+        Some(archive) => {
+            let sha256 = archive.store(&file_name, &location, &raw)?;
+            run.sha256 = Some(sha256.clone());
+            if !options.force && store.persistent() && archive.is_loaded(&file_name, &sha256) {
+                info!("{} is unchanged since the last load (sha256 {}); skipping", file_name, sha256);
+                return Ok(("unchanged", None));
+            }
+            Some((archive, sha256))
+        }
+        None => None,
+    };
+
+    let data = decompress(raw)?;
+    let written = match source {
+        Source::Players => {
+            let (mut players, report) = parse_player_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for p in &mut players {
+                p.team = teams.canonical(&p.team);
+                teams.set_conference(&p.team, season, &p.conf);
+            }
+            info!("Inserting {} player stats for {}", players.len(), season);
+            let written = store.write_players(&players).await?;
+            if let Some(date) = snapshot_date(season, Utc::now()) {
+                store.write_player_history(&player_snapshots(&players, &date)?).await?;
+            }
+            store.write_player_records(&player_records(&players)).await?;
+            Some(written)
+        }
+        Source::Teams => {
+            let (mut team_stats, report) = parse_team_stats(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for t in &mut team_stats {
+                t.team = teams.canonical(&t.team);
+                teams.set_conference(&t.team, season, &t.conf);
+            }
+            info!("Inserting {} team stats for {}", team_stats.len(), season);
+            let written = store.write_team_stats(&team_stats).await?;
+            if let Some(date) = snapshot_date(season, Utc::now()) {
+                store.write_team_history(&team_snapshots(&team_stats, &date)?).await?;
+            }
+            Some(written)
+        }
+        Source::Games => {
+            let (mut games, report) = parse_game_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for g in &mut games {
+                g.tt = teams.canonical(&g.tt);
+                g.opponent = teams.canonical(&g.opponent);
+            }
+            ingest_games(store, season, games).await?
+        }
+        Source::Schedule => {
+            let (mut schedule, report) = parse_schedule_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for g in &mut schedule {
+                teams.canonical_opt(&mut g.team1);
+                teams.canonical_opt(&mut g.team2);
+                teams.canonical_opt(&mut g.winner);
+                teams.canonical_opt(&mut g.loser);
+            }
+            info!("Inserting {} scheduled games for {}", schedule.len(), season);
+            Some(store.write_schedule_games(&schedule).await?)
+        }
+        Source::FourFactors => {
+            let (mut four_factors, report) = parse_four_factors_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for f in &mut four_factors {
+                f.team = teams.canonical(&f.team);
+            }
+            info!("Inserting {} team four-factor rows for {}", four_factors.len(), season);
+            Some(store.write_four_factors(&four_factors).await?)
+        }
+        Source::PlayerShots => {
+            let (mut shot_profiles, report) = parse_player_shot_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for p in &mut shot_profiles {
+                p.team = teams.canonical(&p.team);
+            }
+            info!("Inserting {} player shot profiles for {}", shot_profiles.len(), season);
+            Some(store.write_player_shot_profiles(&shot_profiles).await?)
+        }
+        Source::TeamShooting => {
+            let (mut team_shooting, report) = parse_team_shooting_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for t in &mut team_shooting {
+                t.team = teams.canonical(&t.team);
+            }
+            info!("Inserting {} team shooting rows for {}", team_shooting.len(), season);
+            Some(store.write_team_shooting(&team_shooting).await?)
+        }
+        Source::TeamResults => {
+            let (mut team_results, report) = parse_team_results_data(&data, season)?;
+            check_report(store, run, &report, options.max_drift).await?;
+            for r in &mut team_results {
+                r.team = teams.canonical(&r.team);
+                r.opponent = teams.canonical(&r.opponent);
+            }
+            info!("Inserting {} team game results for {}", team_results.len(), season);
+            Some(store.write_team_results(&team_results).await?)
+        }
+    };
+
+    if let Some((archive, sha256)) = archived
+        && store.persistent()
+    {
+        archive.mark_loaded(&file_name, &sha256)?;
+    }
+    Ok(("loaded", written))
+}
+
+// Writes only the games that are new or changed since the last load, then refreshes
+// averages and percentiles for the player seasons they belong to.
+async fn ingest_games(store: &dyn StatsStore, season: i32, games: Vec<GameStats>) -> Result<Option<WriteReport>, Box<dyn Error>> {
+    let stored = store.season_games(season).await?;
+    let diff = diff_games(games, &stored);
+    info!(
+        "Games for {}: {} new, {} changed, {} unchanged",
+        season, diff.new_games, diff.changed_games, diff.unchanged_games
+    );
+    if diff.to_write.is_empty() {
+        return Ok(None);
+    }
+
+    info!("Inserting {} game stats for {}", diff.to_write.len(), season);
+    let written = store.write_games(&diff.to_write).await?;
+
+    let players: HashSet<(i32, i32, String)> = diff.to_write
+        .iter()
+        .filter_map(|g| Some((g.pid?, g.year?, g.tt.clone())))
+        .collect();
+
+    // The season as now stored: what was there, overlaid with what was just written.
+    let mut season_games: HashMap<_, GameStats> = stored.into_iter().map(|g| (g.key(), g)).collect();
+    season_games.extend(diff.to_write.into_iter().map(|g| (g.key(), g)));
+    let season_games: Vec<GameStats> = season_games.into_values().collect();
+
+    recompute_player_analytics(store, &season_games, &players).await?;
+    Ok(Some(written))
+}
+
+// Logs the write throughput of every feed the run loaded, then the run's total.
+fn log_write_summary(writes: &[WriteReport]) {
+    if writes.is_empty() {
+        return;
+    }
+    info!("Write summary:");
+    for w in writes {
+        info!("  {:<22} {:>8} rows in {:>8.2?} ({:.0} rows/s)", w.table, w.rows, w.elapsed, w.rows_per_sec());
+    }
+    let rows: usize = writes.iter().map(|w| w.rows).sum();
+    let secs: f64 = writes.iter().map(|w| w.elapsed.as_secs_f64()).sum();
+    info!("  {:<22} {:>8} rows in {:>8.2}s ({:.0} rows/s)", "total", rows, secs, if secs > 0.0 { rows as f64 / secs } else { 0.0 });
+}
+
+// Records the parse outcome on the run and stores its rejected rows, then refuses the
+// feed if it drifted too far.
+async fn check_report(store: &dyn StatsStore, run: &mut IngestRun, report: &IngestReport, max_drift: f64) -> Result<(), Box<dyn Error>> {
+    run.record(report);
+    if !report.rejected.is_empty() && let Err(e) = store.write_ingest_rejected_rows(&run.rejected_rows(report)).await {
+        error!("Failed to store the rows {} rejected for {}: {}", run.source, run.year, e);
+    }
+    if let Some(drift) = &report.drift {
+        drift.log();
+        drift.check(max_drift)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use clap::ValueEnum;
+    use crate::memory_store::MemoryStore;
+
+    // The day `IngestRun::start` files a run under.
+    fn today() -> String {
+        Utc::now().format("%Y-%m-%d").to_string()
+    }
+
+    // Two seasons of players (Jane Doe moves from Duke to North Carolina), one season of
+    // team stats and a handful of 2026 games, laid out like a `--from-dir` snapshot.
+    fn fixture_options(seasons: Vec<i32>, sources: Vec<Source>) -> IngestOptions {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot");
+        IngestOptions {
+            seasons,
+            sources,
+            feeds: FeedResolver::new(Some(dir), HashMap::new()),
+            archive_dir: None,
+            force: false,
+            max_drift: 0.05,
+            fetch: FetchConfig {
+                timeout: Duration::from_secs(1),
+                max_retries: 0,
+                base_backoff: Duration::from_secs(0),
+                min_interval: Duration::from_millis(0),
+                cache_dir: None,
+            },
+        }
+    }
+
+    /// A `MemoryStore` holding every fixture feed, as loaded by `run_ingest`.
+    pub(crate) async fn load_fixtures() -> MemoryStore {
+        let store = MemoryStore::default();
+        load_fixtures_into(&store).await;
+        store
+    }
+
+    /// Loads every fixture feed into `store` with `run_ingest`.
+    pub(crate) async fn load_fixtures_into(store: &dyn StatsStore) {
+        run_ingest(store, &fixture_options(vec![2025], vec![Source::Players])).await.unwrap();
+        run_ingest(store, &fixture_options(vec![2026], vec![Source::Players, Source::Teams, Source::Games]))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn from_dir_ingest_writes_every_feed() {
+        let store = load_fixtures().await;
+
+        assert_eq!(store.players("North Carolina", 2026).await.unwrap().len(), 2);
+        assert_eq!(store.players("Duke", 2026).await.unwrap().len(), 1);
+        assert_eq!(store.players("Duke", 2025).await.unwrap().len(), 1);
+        assert_eq!(store.team_stats(2026).await.unwrap().len(), 5);
+        assert_eq!(store.season_games(2026).await.unwrap().len(), 7);
+        assert_eq!(store.season_averages().await.unwrap().len(), 3);
+        assert_eq!(store.season_percentiles().await.unwrap().len(), 3);
+
+        let runs = store.ingest_runs(&today()).await.unwrap();
+        assert_eq!(runs.len(), 4);
+        assert!(runs.iter().all(|r| r.status == "loaded"), "{:?}", runs);
+    }
+
+    #[tokio::test]
+    async fn from_dir_ingest_computes_season_analytics() {
+        let store = load_fixtures().await;
+
+        let averages = store.season_averages().await.unwrap();
+        let jane = averages.iter().find(|a| a.pid == 4242).unwrap();
+        assert_eq!((jane.team.as_str(), jane.games_played), ("North Carolina", 3));
+        assert!((jane.avg_pts - 20.0).abs() < 1e-9);
+
+        // Points per game: Max Poe 30, Jane Doe 20, Sam Roe 10.
+        let percentiles = store.season_percentiles().await.unwrap();
+        let pct_pts = |pid: i32| percentiles.iter().find(|p| p.pid == pid).unwrap().pct_pts;
+        assert!(pct_pts(6161) > pct_pts(4242));
+        assert!(pct_pts(4242) > pct_pts(5151));
+    }
+
+    #[tokio::test]
+    async fn from_dir_ingest_rebuilds_transfers_and_conferences() {
+        let store = load_fixtures().await;
+
+        let transfers = store.transfers(2026).await.unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].pid, 4242);
+        assert_eq!((transfers[0].from_team.as_str(), transfers[0].to_team.as_str()), ("Duke", "North Carolina"));
+
+        let conferences = store.conferences(2026).await.unwrap();
+        assert_eq!(conferences.len(), 2);
+        // Iowa St. and Texas Tech tie on conference win %; Iowa St. has the higher barthag.
+        let standings = store.conference_standings("B12", 2026).await.unwrap();
+        let order: Vec<&str> = standings.iter().map(|s| s.team.as_str()).collect();
+        assert_eq!(order, ["Kansas", "Iowa St.", "Texas Tech"]);
+    }
+
+    #[tokio::test]
+    async fn memory_stores_never_skip_archived_feeds() {
+        let archive = ScratchDir::new("archive-memory");
+        let options = IngestOptions {
+            archive_dir: Some(archive.0.clone()),
+            ..fixture_options(vec![2026], vec![Source::Teams])
+        };
+
+        // Each store starts empty, so the second load must not skip the unchanged feed.
+        for _ in 0..2 {
+            let store = MemoryStore::default();
+            run_ingest(&store, &options).await.unwrap();
+            assert_eq!(store.team_stats(2026).await.unwrap().len(), 5);
+        }
+    }
+
+    // A scratch `--from-dir` snapshot, removed when dropped.
+    struct ScratchDir(PathBuf);
+
+    impl ScratchDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("mater-{}-{}", name, std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            ScratchDir(dir)
+        }
+
+        fn options(&self, sources: Vec<Source>, max_drift: f64) -> IngestOptions {
+            IngestOptions {
+                feeds: FeedResolver::new(Some(self.0.clone()), HashMap::new()),
+                max_drift,
+                ..fixture_options(vec![2026], sources)
+            }
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // Four rows of two text columns, which match no feed's layout.
+    fn unrecognizable_feed(source: Source) -> String {
+        let rows = "x,x\n".repeat(4);
+        match source {
+            Source::FourFactors => format!("team,adjoe\n{}", rows),
+            Source::Players | Source::Schedule | Source::TeamResults => rows,
+            Source::Teams | Source::Games | Source::PlayerShots | Source::TeamShooting => {
+                r#"[["x","x"],["x","x"],["x","x"],["x","x"]]"#.to_string()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn every_feed_stops_past_the_drift_limit() {
+        let dir = ScratchDir::new("drift-every-feed");
+        let sources = Source::value_variants().to_vec();
+        for &source in &sources {
+            fs::write(dir.0.join(source.file_name(2026)), unrecognizable_feed(source)).unwrap();
+        }
+
+        let store = MemoryStore::default();
+        assert!(run_ingest(&store, &dir.options(sources.clone(), 0.99)).await.is_err());
+        let runs = store.ingest_runs(&today()).await.unwrap();
+        assert_eq!(runs.len(), sources.len());
+        for run in &runs {
+            assert_eq!(run.status, "failed", "{}", run.source);
+            let error = run.error.as_deref().unwrap_or_default();
+            assert!(error.contains("refusing to write"), "{}: {}", run.source, error);
+        }
+
+        // At a limit of 1 no feed is refused; its rows are parsed (or rejected) one by one.
+        let store = MemoryStore::default();
+        let _ = run_ingest(&store, &dir.options(sources.clone(), 1.0)).await;
+        let runs = store.ingest_runs(&today()).await.unwrap();
+        assert_eq!(runs.len(), sources.len());
+        for run in &runs {
+            assert!(!run.error.as_deref().unwrap_or_default().contains("refusing to write"), "{}", run.source);
+            assert_eq!(run.rows_seen, Some(4), "{}", run.source);
+            let rejected = store.ingest_rejected_rows(&today(), &run.source, 2026).await.unwrap();
+            assert_eq!(Some(rejected.len() as i32), run.rows_rejected, "{}", run.source);
+        }
+    }
+
+    #[tokio::test]
+    async fn drift_limit_is_a_fraction_of_rows() {
+        let fixture = fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot/2026_team_results.json")).unwrap();
+        let mut rows: Vec<serde_json::Value> = serde_json::from_str(&fixture).unwrap();
+        // One of the five teams has text where adjoe belongs.
+        rows[0][4] = serde_json::json!("n/a");
+        let dir = ScratchDir::new("drift-limit");
+        fs::write(dir.0.join(Source::Teams.file_name(2026)), serde_json::to_string(&rows).unwrap()).unwrap();
+
+        let store = MemoryStore::default();
+        assert!(run_ingest(&store, &dir.options(vec![Source::Teams], 0.19)).await.is_err());
+        assert!(store.team_stats(2026).await.unwrap().is_empty());
+
+        run_ingest(&store, &dir.options(vec![Source::Teams], 0.2)).await.unwrap();
+        assert_eq!(store.team_stats(2026).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn reloading_games_writes_only_new_and_changed_rows() {
+        let store = load_fixtures().await;
+        let max_poe = season_averages(&store, 6161).await;
+
+        let fixture = fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot/2026_all_advgames.json")).unwrap();
+        let mut rows: Vec<serde_json::Value> = serde_json::from_str(&fixture).unwrap();
+        // Jane Doe's first game is corrected from 10 to 40 points, and Sam Roe gains a
+        // third game of 25 points.
+        rows[0][33] = serde_json::json!(40.0);
+        let mut new_game = rows[4].clone();
+        new_game[0] = serde_json::json!("20251124");
+        new_game[33] = serde_json::json!(25.0);
+        rows.push(new_game);
+        let feed = serde_json::to_string(&rows).unwrap();
+
+        let (games, _) = parse_game_data(feed.as_bytes(), 2026).unwrap();
+        let diff = diff_games(games, &store.season_games(2026).await.unwrap());
+        assert_eq!(diff.to_write.len(), 2);
+        assert_eq!((diff.new_games, diff.changed_games, diff.unchanged_games), (1, 1, 6));
+
+        let dir = ScratchDir::new("games-reload");
+        fs::write(dir.0.join(Source::Games.file_name(2026)), feed).unwrap();
+        run_ingest(&store, &dir.options(vec![Source::Games], 0.05)).await.unwrap();
+
+        assert_eq!(store.season_games(2026).await.unwrap().len(), 8);
+        assert_eq!(season_averages(&store, 4242).await["avg_pts"], 30.0);
+        let sam = season_averages(&store, 5151).await;
+        assert_eq!((sam["games_played"].as_i64(), sam["avg_pts"].as_f64()), (Some(3), Some(15.0)));
+        assert_eq!(season_averages(&store, 6161).await, max_poe);
+    }
+
+    // A player's stored season averages, as JSON so whole rows can be compared.
+    async fn season_averages(store: &MemoryStore, pid: i32) -> serde_json::Value {
+        let averages = store.season_averages().await.unwrap();
+        serde_json::to_value(averages.iter().find(|a| a.pid == pid).unwrap()).unwrap()
+    }
+}
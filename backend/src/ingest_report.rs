@@ -0,0 +1,273 @@
+// src/ingest_report.rs
+use csv::StringRecord;
+use log::{info, error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::schema_drift::DriftReport;
+
+/// A feed row a parser could not turn into a record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedRow {
+    /// Position of the row in the feed, counting from 0.
+    pub index: usize,
+    /// The row as it appeared in the feed (a JSON array of its fields).
+    pub raw: String,
+    pub reason: String,
+}
+
+/// What a parser did with a feed: how many rows it kept, every row it rejected and why,
+/// and how far the feed drifted from its expected layout.
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestReport {
+    pub feed: String,
+    pub rows_accepted: usize,
+    pub rejected: Vec<RejectedRow>,
+    pub drift: Option<DriftReport>,
+}
+
+impl IngestReport {
+    pub fn new(feed: &str) -> Self {
+        IngestReport {
+            feed: feed.to_string(),
+            rows_accepted: 0,
+            rejected: Vec::new(),
+            drift: None,
+        }
+    }
+
+    pub fn accept(&mut self) {
+        self.rows_accepted += 1;
+    }
+
+    /// Records a rejected row. Only the first few are logged; all of them are kept.
+    pub fn reject(&mut self, index: usize, raw: String, reason: String) {
+        if self.rejected.len() < 5 {
+            error!("Error deserializing {} row {}: {}", self.feed, index, reason);
+            error!("Problematic row data: {}", raw);
+        } else if self.rejected.len() == 5 {
+            error!("... (further deserialization errors suppressed)");
+        }
+        self.rejected.push(RejectedRow { index, raw, reason });
+    }
+
+    pub fn with_drift(mut self, drift: DriftReport) -> Self {
+        self.drift = Some(drift);
+        self
+    }
+
+    pub fn rows_seen(&self) -> usize {
+        self.rows_accepted + self.rejected.len()
+    }
+
+    pub fn log_summary(&self) {
+        info!("Successfully parsed and collected {} {} records.", self.rows_accepted, self.feed);
+        if !self.rejected.is_empty() {
+            info!("Encountered {} errors during deserialization.", self.rejected.len());
+        }
+    }
+}
+
+/// A CSV record as a JSON array of its fields, for `RejectedRow::raw`.
+pub fn csv_raw(record: &StringRecord) -> String {
+    serde_json::to_string(&record.iter().collect::<Vec<_>>()).unwrap_or_default()
+}
+
+/// A JSON feed row as written in the feed, for `RejectedRow::raw`.
+pub fn json_raw(row: &[serde_json::Value]) -> String {
+    serde_json::to_string(row).unwrap_or_default()
+}
+
+/// One feed loaded for one season by one `mater ingest` run, as stored in `ingest_runs`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct IngestRun {
+    /// UTC day the run started (YYYY-MM-DD); runs are partitioned by day.
+    pub run_date: String,
+    pub started_at: String,
+    pub source: String,
+    pub year: i32,
+    pub finished_at: Option<String>,
+    /// `loaded`, `unchanged` (same content as the last load) or `failed`.
+    pub status: String,
+    pub location: Option<String>,
+    pub sha256: Option<String>,
+    pub rows_seen: Option<i32>,
+    pub rows_accepted: Option<i32>,
+    /// How many rows the parser rejected; the rows themselves are in `ingest_rejected_rows`.
+    pub rows_rejected: Option<i32>,
+    /// Stored as JSON text; served as JSON.
+    #[serde(serialize_with = "as_json", deserialize_with = "from_json")]
+    pub drift: Option<String>,
+    pub error: Option<String>,
+}
+
+fn as_json<S: Serializer>(text: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    let value: Option<serde_json::Value> = text.as_deref().and_then(|t| serde_json::from_str(t).ok());
+    value.serialize(serializer)
+}
+
+// Reads back what `as_json` wrote, as JSON text again.
+fn from_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value: Option<serde_json::Value> = Option::deserialize(deserializer)?;
+    Ok(value.map(|v| v.to_string()))
+}
+
+impl IngestRun {
+    pub fn start(source: &str, year: i32) -> Self {
+        let now = chrono::Utc::now();
+        IngestRun {
+            run_date: now.format("%Y-%m-%d").to_string(),
+            started_at: now.to_rfc3339(),
+            source: source.to_string(),
+            year,
+            finished_at: None,
+            status: "failed".to_string(),
+            location: None,
+            sha256: None,
+            rows_seen: None,
+            rows_accepted: None,
+            rows_rejected: None,
+            drift: None,
+            error: None,
+        }
+    }
+
+    /// Copies a parser's report into the run.
+    pub fn record(&mut self, report: &IngestReport) {
+        self.rows_seen = Some(report.rows_seen() as i32);
+        self.rows_accepted = Some(report.rows_accepted as i32);
+        self.rows_rejected = Some(report.rejected.len() as i32);
+        self.drift = report.drift.as_ref().and_then(|d| serde_json::to_string(d).ok());
+    }
+
+    pub fn finish(&mut self, status: &str, error: Option<String>) {
+        self.status = status.to_string();
+        self.error = error;
+        self.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    /// Every row the parser rejected, filed under this run.
+    pub fn rejected_rows(&self, report: &IngestReport) -> Vec<IngestRejectedRow> {
+        report
+            .rejected
+            .iter()
+            .map(|r| IngestRejectedRow {
+                run_date: self.run_date.clone(),
+                source: self.source.clone(),
+                year: self.year,
+                started_at: self.started_at.clone(),
+                row_index: r.index as i32,
+                raw: r.raw.clone(),
+                reason: r.reason.clone(),
+            })
+            .collect()
+    }
+}
+
+/// A row an ingest run rejected, as stored in `ingest_rejected_rows`, which holds all of
+/// them however many a feed that changed shape produces.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct IngestRejectedRow {
+    pub run_date: String,
+    pub source: String,
+    pub year: i32,
+    /// The `started_at` of the run that rejected the row.
+    pub started_at: String,
+    /// Position of the row in the feed, counting from 0.
+    pub row_index: i32,
+    pub raw: String,
+    pub reason: String,
+}
+
+pub async fn insert_ingest_run(session: &Session, run: &IngestRun) -> Result<(), QueryError> {
+    let query = r#"
+    INSERT INTO ingest_runs (
+        run_date, started_at, source, year, finished_at, status, location, sha256,
+        rows_seen, rows_accepted, rows_rejected, drift, error
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
+    let prepared = session.prepare(query).await?;
+    session.execute(&prepared, run).await?;
+    Ok(())
+}
+
+/// Fetches every feed run started on a UTC day (YYYY-MM-DD), newest first.
+pub async fn get_ingest_runs_from_db(session: &Session, run_date: &str) -> Result<Vec<IngestRun>, QueryError> {
+    let query = r#"
+    SELECT run_date, started_at, source, year, finished_at, status, location, sha256,
+           rows_seen, rows_accepted, rows_rejected, drift, error
+    FROM ingest_runs WHERE run_date = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (run_date,)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut runs = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match IngestRun::from_row(row) {
+            Ok(r) => runs.push(r),
+            Err(e) => error!("Ingest run row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(runs)
+}
+
+pub async fn insert_ingest_rejected_rows(session: &Session, rows: &[IngestRejectedRow]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO ingest_rejected_rows (run_date, source, year, started_at, row_index, raw, reason)
+    VALUES (?, ?, ?, ?, ?, ?, ?)
+"#;
+
+    write_batched(session, "ingest_rejected_rows", query, rows, |r| (r.run_date.clone(), r.source.clone(), r.year)).await
+}
+
+/// Fetches every row a feed's runs rejected for a season on a UTC day (YYYY-MM-DD).
+pub async fn get_ingest_rejected_rows_from_db(
+    session: &Session,
+    run_date: &str,
+    source: &str,
+    year: i32,
+) -> Result<Vec<IngestRejectedRow>, QueryError> {
+    let query = r#"
+    SELECT run_date, source, year, started_at, row_index, raw, reason
+    FROM ingest_rejected_rows WHERE run_date = ? AND source = ? AND year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (run_date, source, year)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut rejected = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match IngestRejectedRow::from_row(row) {
+            Ok(r) => rejected.push(r),
+            Err(e) => error!("Rejected row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_keep_every_rejected_row() {
+        let mut report = IngestReport::new("players");
+        for index in 0..250 {
+            report.reject(index, "[]".to_string(), "too short".to_string());
+        }
+        let mut run = IngestRun::start("players", 2026);
+        run.record(&report);
+
+        assert_eq!(run.rows_rejected, Some(250));
+        let stored = run.rejected_rows(&report);
+        assert_eq!(stored.len(), 250);
+        assert_eq!((stored[0].row_index, stored[249].row_index), (0, 249));
+        assert!(stored.iter().all(|r| r.started_at == run.started_at && r.source == "players" && r.year == 2026));
+    }
+}
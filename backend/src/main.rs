@@ -1,30 +1,86 @@
 use std::error::Error;
+use std::sync::Arc;
 use log::{error, info};
-use env_logger;
 use actix_web::{get, web, App, HttpServer, HttpResponse, Responder};
-use scylla::{Session, FromRow};
 use chrono::{Utc, Duration};
+use clap::Parser;
 
-mod init_db;
 mod get_team_stats;
 mod get_player_stats;
 mod db_utils;
 mod get_game_stats;
 mod analytics_types;
 mod analytics_calculator;
+mod get_schedule;
+mod get_four_factors;
+mod get_player_shots;
+mod get_team_shooting;
+mod get_team_results;
+mod cli;
+mod ingest;
+mod feed_source;
+mod feed_archive;
+mod schema_drift;
+mod ingest_report;
+mod http_client;
+mod scheduler;
+mod analytics_snapshot;
+mod batch_writer;
+mod stats_store;
+mod scylla_store;
+mod memory_store;
+mod migrations;
+mod db_config;
+mod rating_history;
+mod player_registry;
+mod team_registry;
+mod transfers;
+mod conferences;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+
+use crate::db_utils::connect_to_scylla;
+use crate::analytics_calculator::{recompute_season_analytics, calculate_player_averages_by_date_range};
+use crate::cli::{Cli, Command, MigrateArgs, ServeArgs, StoreKind, current_season};
+use crate::migrations::{check_schema_version, connect_for_migrations, log_migration_status, run_migrations};
+use crate::ingest::run_ingest;
+use crate::analytics_snapshot::{AnalyticsSnapshot, SharedAnalytics};
+use crate::scheduler::{refresh, run_scheduled_refreshes, run_snapshot_reloads};
+use crate::stats_store::StatsStore;
+use crate::scylla_store::ScyllaStore;
+use crate::memory_store::MemoryStore;
+use crate::rating_history::trajectory;
+use crate::transfers::refresh_transfers;
+use crate::conferences::{ConferenceTable, refresh_conferences};
+use crate::analytics_types::{PlayerStatsWithPercentiles, PlayerSeasonPercentiles, TeamProfile};
+
+// The canonical spelling of a `team` query param, so any alias or abbreviation in the
+// team registry works. Unknown names (and registry errors) pass through unchanged.
+async fn canonical_team(store: &dyn StatsStore, team: &str) -> String {
+    match store.team_name(team).await {
+        Ok(Some(name)) => name,
+        Ok(None) => team.to_string(),
+        Err(e) => {
+            error!("Failed to look up team '{}': {}", team, e);
+            team.to_string()
+        }
+    }
+}
 
-use crate::get_team_stats::{get_team_stats, insert_team_stats, TeamStats};
-use crate::get_player_stats::{get_player_data, insert_player_stats, PlayerStats};
-use crate::init_db::init_db;
-use crate::db_utils::{connect_to_scylla, query_specific_player, get_players_from_db};
-use crate::get_game_stats::{get_game_data, insert_game_stats, GameStats, get_all_game_stats_from_db};
-use crate::analytics_calculator::{
-    calculate_and_insert_season_averages,
-    calculate_and_insert_season_percentiles,
-    get_all_player_season_averages_from_db,
-    calculate_player_averages_by_date_range
-};
-use crate::analytics_types::{PlayerSeasonAverages, PlayerStatsWithPercentiles, PlayerSeasonPercentiles};
+// The team registry: canonical names, abbreviations, aliases and conference by season
+#[get("/api/teams")]
+async fn get_teams_endpoint(store: web::Data<dyn StatsStore>) -> impl Responder {
+    match store.teams().await {
+        Ok(mut teams) => {
+            teams.sort_by(|a, b| a.name.cmp(&b.name));
+            HttpResponse::Ok().json(teams)
+        }
+        Err(e) => {
+            error!("Failed to query team registry: {}", e);
+            HttpResponse::InternalServerError().body("Query failed")
+        }
+    }
+}
 
 #[get("/api/hello")]
 async fn hello() -> impl Responder {
@@ -33,14 +89,9 @@ async fn hello() -> impl Responder {
 
 #[get("/api/players")]
 async fn get_players_endpoint(
-    db: web::Data<Session>,
+    store: web::Data<dyn StatsStore>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
-    let team_code = match query.get("team") {
-        Some(code) => code,
-        None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
-    };
-
     let year = match query.get("year") {
         Some(y) => match y.parse::<i32>() {
             Ok(n) => n,
@@ -49,17 +100,29 @@ async fn get_players_endpoint(
         None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
     };
 
-    let result = get_players_from_db(&db, team_code, year).await;
+    // A team's roster, or with `pid` instead of `team`, one player's rows for every team
+    // they played for that season.
+    let (result, subject) = match (query.get("team"), query.get("pid")) {
+        (Some(team_code), _) => {
+            let team_code = canonical_team(store.get_ref(), team_code).await;
+            (store.players(&team_code, year).await, format!("team {}", team_code))
+        }
+        (None, Some(p)) => match p.parse::<i32>() {
+            Ok(pid) => (store.player_seasons(pid, year).await, format!("pid {}", pid)),
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'pid' query param"),
+        },
+        (None, None) => return HttpResponse::BadRequest().body("Missing 'team' or 'pid' query param"),
+    };
 
     let mut players = match result {
         Ok(p) => p,
         Err(e) => {
-            error!("Failed to query Scylla for players: {e}");
+            error!("Failed to query players: {e}");
             return HttpResponse::InternalServerError().body(format!("Query failed: {e}"));
         }
     };
 
-    info!("Returned {} rows for team {}", players.len(), team_code);
+    info!("Returned {} rows for {}", players.len(), subject);
 
     players.sort_by(|a, b| {
         b.mp.partial_cmp(&a.mp).unwrap_or(std::cmp::Ordering::Equal)
@@ -68,55 +131,114 @@ async fn get_players_endpoint(
     HttpResponse::Ok().json(players)
 }
 
+// A player's canonical name, aliases and team history from the registry, by pid alone
+#[get("/api/player")]
+async fn get_player_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let pid = match query.get("pid") {
+        Some(p) => match p.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'pid' query param"),
+        },
+        None => return HttpResponse::BadRequest().body("Missing 'pid' query param"),
+    };
+
+    match store.player_record(pid).await {
+        Ok(Some(record)) => HttpResponse::Ok().json(record.identity()),
+        Ok(None) => HttpResponse::NotFound().body(format!("No player with pid {}", pid)),
+        Err(e) => {
+            error!("Failed to query player registry: {}", e);
+            HttpResponse::InternalServerError().body("Query failed")
+        }
+    }
+}
+
 #[get("/api/team-stats")]
 async fn get_team_stats_endpoint(
-    db: web::Data<Session>,
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
-    let query_cql = r#"
-        SELECT rank, team, conf, record, adjoe, adjoe_rank, adjde, adjde_rank, barthag, barthag_rank,
-               proj_wins, proj_losses, proj_conf_wins, proj_conf_losses, conf_record,
-               sos, nconf_sos, conf_sos, proj_sos, proj_nconf_sos, proj_conf_sos,
-               elite_sos, elite_ncsos, opp_adjoe, opp_adjde, opp_proj_adjoe, opp_proj_adjde,
-               conf_adjoe, conf_adjde, qual_adjoe, qual_adjde, qual_barthag, qual_games,
-               fun, conf_pf, conf_pa, conf_poss, conf_adj_o, conf_adj_d, conf_sos_remain,
-               conf_win_perc, wab, wab_rank, fun_rank, adj_tempo
-        FROM stats.team_stats
-    "#;
-
-    let prepared = match db.prepare(query_cql).await {
-        Ok(stmt) => stmt,
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => current_season(),
+    };
+
+    let mut stats = match store.team_stats(year).await {
+        Ok(stats) => stats,
         Err(e) => {
-            error!("Failed to prepare query: {}", e);
-            return HttpResponse::InternalServerError().body("Failed to prepare query");
+            error!("Failed to query team stats: {}", e);
+            return HttpResponse::InternalServerError().body("Query failed");
         }
     };
 
-    let result = db.execute(&prepared, ()).await;
+    stats.sort_by_key(|s| s.rank);
 
-    let rows = match result {
-        Ok(res) => res.rows.unwrap_or_default(),
+    // Four factors and shooting splits are optional: a season may not have those feeds loaded yet.
+    let mut four_factors: std::collections::HashMap<String, _> = match store.four_factors(year).await {
+        Ok(rows) => rows.into_iter().map(|f| (f.team.clone(), f)).collect(),
         Err(e) => {
-            error!("Failed to query team stats: {}", e);
-            return HttpResponse::InternalServerError().body("Query failed");
+            error!("Failed to query four factors for {}: {}", year, e);
+            std::collections::HashMap::new()
         }
     };
 
-    let mut stats = Vec::new();
-    for (i, row) in rows.into_iter().enumerate() {
-        match TeamStats::from_row(row) {
-            Ok(stat) => stats.push(stat),
-            Err(e) => error!("Failed to parse row {}: {}", i, e),
+    let mut shooting: std::collections::HashMap<String, _> = match store.team_shooting(year).await {
+        Ok(rows) => rows.into_iter().map(|s| (s.team.clone(), s)).collect(),
+        Err(e) => {
+            error!("Failed to query team shooting for {}: {}", year, e);
+            std::collections::HashMap::new()
         }
-    }
+    };
 
-    stats.sort_by_key(|s| s.rank);
+    let profiles: Vec<TeamProfile> = stats.into_iter().map(|stats| TeamProfile {
+        four_factors: four_factors.remove(&stats.team),
+        shooting: shooting.remove(&stats.team),
+        stats,
+    }).collect();
 
-    HttpResponse::Ok().json(stats)
+    HttpResponse::Ok().json(profiles)
+}
+
+// Team game log: opponent, venue, score and efficiency for every game of a season
+#[get("/api/team-results")]
+async fn get_team_results_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let team_code = match query.get("team") {
+        Some(code) => &canonical_team(store.get_ref(), code).await,
+        None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
+    };
+
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
+    };
+
+    let mut results = match store.team_results(team_code, year).await {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to query team results: {}", e);
+            return HttpResponse::InternalServerError().body("Query failed");
+        }
+    };
+
+    results.sort_by(|a, b| a.numdate.cmp(&b.numdate));
+
+    HttpResponse::Ok().json(results)
 }
 
 #[get("/api/game-stats")]
 async fn get_game_stats_endpoint(
-    db: web::Data<Session>,
+    store: web::Data<dyn StatsStore>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
     let pid = match query.get("pid") {
@@ -135,43 +257,31 @@ async fn get_game_stats_endpoint(
         None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
     };
 
-    let team = match query.get("team") {
-        Some(t) => t.to_string(),
-        None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
-    };
-
-    let query_cql = r#"
-        SELECT numdate, datetext, opstyle, quality, win1, opponent, muid, win2, min_per, o_rtg, usage,
-               e_fg, ts_per, orb_per, drb_per, ast_per, to_per, dunks_made, dunks_att, rim_made,
-               rim_att, mid_made, mid_att, two_pm, two_pa, tpm, tpa, ftm, fta, bpm_rd, obpm,
-               dbpm, bpm_net, pts, orb, drb, ast, tov, stl, blk, stl_per, blk_per, pf,
-               possessions, bpm, sbpm, loc, tt, pp, inches, cls, pid, year
-        FROM stats.game_stats WHERE pid = ? AND year = ? AND tt = ?
-    "#;
-
-    let prepared = match db.prepare(query_cql).await {
-        Ok(stmt) => stmt,
-        Err(e) => {
-            error!("Failed to prepare query: {}", e);
-            return HttpResponse::InternalServerError().body("Failed to prepare query");
-        }
-    };
-
-    let result = db.execute(&prepared, (pid, year, team.as_str())).await;
-
-    let rows = match result {
-        Ok(res) => res.rows.unwrap_or_default(),
-        Err(e) => {
-            error!("Failed to query game stats: {}", e);
-            return HttpResponse::InternalServerError().body("Query failed");
-        }
+    // Without `team`, the registry says who the player played for that season: every team,
+    // if they moved mid-season.
+    let teams: Vec<String> = match query.get("team") {
+        Some(t) => vec![canonical_team(store.get_ref(), t).await],
+        None => match store.player_record(pid).await {
+            Ok(Some(record)) => match record.teams_in(year) {
+                Some(teams) => teams.iter().cloned().collect(),
+                None => return HttpResponse::NotFound().body(format!("Player {} has no team in {}", pid, year)),
+            },
+            Ok(None) => return HttpResponse::NotFound().body(format!("No player with pid {}", pid)),
+            Err(e) => {
+                error!("Failed to query player registry: {}", e);
+                return HttpResponse::InternalServerError().body("Query failed");
+            }
+        },
     };
 
     let mut game_stats = Vec::new();
-    for (i, row) in rows.into_iter().enumerate() {
-        match GameStats::from_row(row) {
-            Ok(stat) => game_stats.push(stat),
-            Err(e) => error!("Failed to parse row {}: {}", i, e),
+    for team in &teams {
+        match store.player_games(pid, year, team).await {
+            Ok(games) => game_stats.extend(games),
+            Err(e) => {
+                error!("Failed to query game stats: {}", e);
+                return HttpResponse::InternalServerError().body("Query failed");
+            }
         }
     }
 
@@ -180,15 +290,15 @@ async fn get_game_stats_endpoint(
     HttpResponse::Ok().json(game_stats)
 }
 
-// NEW API ENDPOINT: Fetch player season averages for a given team and year
-#[get("/api/player-season-averages")]
-async fn get_player_season_averages_endpoint(
-    db: web::Data<Session>,
+// Look up the scheduled game behind a game_stats row's `muid`
+#[get("/api/schedule-game")]
+async fn get_schedule_game_endpoint(
+    store: web::Data<dyn StatsStore>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
-    let team_code = match query.get("team") {
-        Some(code) => code,
-        None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
+    let muid = match query.get("muid") {
+        Some(m) => m,
+        None => return HttpResponse::BadRequest().body("Missing 'muid' query param"),
     };
 
     let year = match query.get("year") {
@@ -199,48 +309,89 @@ async fn get_player_season_averages_endpoint(
         None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
     };
 
-    let query_cql = r#"
-        SELECT pid, year, team, player_name, games_played, avg_min_per, avg_o_rtg, avg_usg, avg_e_fg, avg_ts_per, avg_orb_per, avg_drb_per, avg_ast_per, avg_to_per, avg_dunks_made, avg_dunks_att, avg_rim_made, avg_rim_att, avg_mid_made, avg_mid_att, avg_two_pm, avg_two_pa, avg_tpm, avg_tpa, avg_ftm, avg_fta, avg_bpm_rd, avg_obpm, avg_dbpm, avg_bpm_net, avg_pts, avg_orb, avg_drb, avg_ast, avg_tov, avg_stl, avg_blk, avg_stl_per, avg_blk_per, avg_pf, avg_possessions, avg_bpm, avg_sbpm, avg_inches, avg_opstyle, avg_quality, avg_win1, avg_win2
-        FROM stats.player_season_avg_stats WHERE team = ? AND year = ?
-    "#;
-
-    let prepared = match db.prepare(query_cql).await {
-        Ok(stmt) => stmt,
+    match store.schedule_game(muid, year).await {
+        Ok(Some(game)) => HttpResponse::Ok().json(game),
+        Ok(None) => HttpResponse::NotFound().body(format!("No scheduled game with muid '{}' in {}", muid, year)),
         Err(e) => {
-            error!("Failed to prepare query for player season averages: {}", e);
-            return HttpResponse::InternalServerError().body("Failed to prepare query");
+            error!("Failed to query schedule game: {}", e);
+            HttpResponse::InternalServerError().body("Query failed")
         }
+    }
+}
+
+// Shot-distance profiles (rim/mid/three frequency, accuracy and assisted share) for a team's players
+#[get("/api/player-shot-profiles")]
+async fn get_player_shot_profiles_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let team_code = match query.get("team") {
+        Some(code) => &canonical_team(store.get_ref(), code).await,
+        None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
     };
 
-    let result = db.execute(&prepared, (team_code, year)).await;
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
+    };
+
+    let pid = match query.get("pid") {
+        Some(p) => match p.parse::<i32>() {
+            Ok(n) => Some(n),
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'pid' query param"),
+        },
+        None => None,
+    };
 
-    let rows = match result {
-        Ok(res) => res.rows.unwrap_or_default(),
+    let mut profiles = match store.player_shot_profiles(team_code, year).await {
+        Ok(p) => p,
         Err(e) => {
-            error!("Failed to query player season averages: {}", e);
+            error!("Failed to query player shot profiles: {}", e);
             return HttpResponse::InternalServerError().body("Query failed");
         }
     };
 
-    let mut player_averages = Vec::new();
-    for (i, row) in rows.into_iter().enumerate() {
-        match PlayerSeasonAverages::from_row(row) {
-            Ok(avg) => player_averages.push(avg),
-            Err(e) => error!("Failed to parse player season average row {}: {}", i, e),
-        }
+    if let Some(pid) = pid {
+        profiles.retain(|p| p.pid == pid);
     }
 
-    HttpResponse::Ok().json(player_averages)
+    HttpResponse::Ok().json(profiles)
+}
+
+// NEW API ENDPOINT: Fetch player season averages for a given team and year
+#[get("/api/player-season-averages")]
+async fn get_player_season_averages_endpoint(
+    store: web::Data<dyn StatsStore>,
+    analytics: web::Data<SharedAnalytics>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let team_code = match query.get("team") {
+        Some(code) => &canonical_team(store.get_ref(), code).await,
+        None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
+    };
+
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
+    };
+
+    HttpResponse::Ok().json(analytics.current().averages(team_code, year))
 }
 
 // NEW API ENDPOINT: Fetch player rolling averages for the last N days (default 30)
 #[get("/api/player-rolling-averages")]
 async fn get_player_rolling_averages_endpoint(
-    db: web::Data<Session>,
+    store: web::Data<dyn StatsStore>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
     let team_code = match query.get("team") {
-        Some(code) => code,
+        Some(code) => &canonical_team(store.get_ref(), code).await,
         None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
     };
 
@@ -270,22 +421,29 @@ async fn get_player_rolling_averages_endpoint(
 
     info!("Date range: {} to {}", start_date_str, end_date_str);
 
-    // Fetch all game stats from database
-    let all_game_stats = match get_all_game_stats_from_db(&db).await {
+    // Fetch the team's games for the season (a single partition)
+    let all_game_stats = match store.team_games(team_code, year).await {
         Ok(stats) => stats,
         Err(e) => {
-            error!("Failed to fetch game stats from database: {}", e);
+            error!("Failed to fetch game stats: {}", e);
             return HttpResponse::InternalServerError().body(format!("Failed to fetch game stats: {}", e));
         }
     };
 
+    // Season-long constants come from the team's player_stats rows, looked up by pid
+    let season_stats: std::collections::HashMap<i32, _> = match store.players(team_code, year).await {
+        Ok(players) => players.into_iter().filter_map(|p| Some((p.pid?, p))).collect(),
+        Err(e) => {
+            error!("Failed to fetch player stats: {}", e);
+            std::collections::HashMap::new()
+        }
+    };
+
     // Find all unique players for this team and year
     let mut player_keys: std::collections::HashSet<(i32, String)> = std::collections::HashSet::new();
     for game in &all_game_stats {
         if let Some(pid) = game.pid {
-            if game.year == Some(year) && game.tt == *team_code {
-                player_keys.insert((pid, game.pp.clone()));
-            }
+            player_keys.insert((pid, game.pp.clone()));
         }
     }
 
@@ -315,45 +473,15 @@ async fn get_player_rolling_averages_endpoint(
                 adjoe: None,
             };
 
-            // Fetch season-long constants from player_stats table
-            let query_player_stats = r#"
-                SELECT conf, player_type, yr, ht, porpag, dporpag, drtg, adjoe
-                FROM stats.player_stats WHERE pid = ? AND year = ? AND team = ? ALLOW FILTERING
-            "#;
-
-            if let Ok(prepared) = db.prepare(query_player_stats).await {
-                if let Ok(result) = db.execute(&prepared, (pid, year, team_code)).await {
-                    if let Some(rows) = result.rows {
-                        if let Some(row) = rows.into_iter().next() {
-                            // Extract the fields manually (string fields)
-                            if let Some(conf) = row.columns[0].as_ref().and_then(|v| v.as_text()).map(|s| s.to_string()) {
-                                rolling_avg.conf = Some(conf);
-                            }
-                            if let Some(player_type) = row.columns[1].as_ref().and_then(|v| v.as_text()).map(|s| s.to_string()) {
-                                rolling_avg.player_type = Some(player_type);
-                            }
-                            if let Some(yr) = row.columns[2].as_ref().and_then(|v| v.as_text()).map(|s| s.to_string()) {
-                                rolling_avg.yr = Some(yr);
-                            }
-                            if let Some(ht) = row.columns[3].as_ref().and_then(|v| v.as_text()).map(|s| s.to_string()) {
-                                rolling_avg.ht = Some(ht);
-                            }
-                            // Extract numeric fields
-                            if let Some(porpag) = row.columns[4].as_ref().and_then(|v| v.as_double()) {
-                                rolling_avg.porpag = Some(porpag);
-                            }
-                            if let Some(dporpag) = row.columns[5].as_ref().and_then(|v| v.as_double()) {
-                                rolling_avg.dporpag = Some(dporpag);
-                            }
-                            if let Some(drtg) = row.columns[6].as_ref().and_then(|v| v.as_double()) {
-                                rolling_avg.drtg = Some(drtg);
-                            }
-                            if let Some(adjoe) = row.columns[7].as_ref().and_then(|v| v.as_double()) {
-                                rolling_avg.adjoe = Some(adjoe);
-                            }
-                        }
-                    }
-                }
+            if let Some(stats) = season_stats.get(&pid) {
+                rolling_avg.conf = Some(stats.conf.clone());
+                rolling_avg.player_type = stats.player_type.clone();
+                rolling_avg.yr = stats.yr.clone();
+                rolling_avg.ht = stats.ht.clone();
+                rolling_avg.porpag = stats.porpag;
+                rolling_avg.dporpag = stats.dporpag;
+                rolling_avg.drtg = stats.drtg;
+                rolling_avg.adjoe = stats.adjoe;
             }
 
             rolling_averages.push(rolling_avg);
@@ -503,11 +631,12 @@ async fn get_player_rolling_averages_endpoint(
 // NEW API ENDPOINT: Fetch player season averages with percentiles
 #[get("/api/player-stats-with-percentiles")]
 async fn get_player_stats_with_percentiles_endpoint(
-    db: web::Data<Session>,
+    store: web::Data<dyn StatsStore>,
+    analytics: web::Data<SharedAnalytics>,
     query: web::Query<std::collections::HashMap<String, String>>,
 ) -> impl Responder {
     let team_code = match query.get("team") {
-        Some(code) => code,
+        Some(code) => &canonical_team(store.get_ref(), code).await,
         None => return HttpResponse::BadRequest().body("Missing 'team' query param"),
     };
 
@@ -521,77 +650,17 @@ async fn get_player_stats_with_percentiles_endpoint(
 
     info!("Fetching player stats with percentiles for team: {}, year: {}", team_code, year);
 
-    // Fetch averages from database
-    let query_cql_avg = r#"
-        SELECT pid, year, team, player_name, games_played, avg_min_per, avg_o_rtg, avg_usg, avg_e_fg, avg_ts_per, avg_orb_per, avg_drb_per, avg_ast_per, avg_to_per, avg_dunks_made, avg_dunks_att, avg_rim_made, avg_rim_att, avg_mid_made, avg_mid_att, avg_two_pm, avg_two_pa, avg_tpm, avg_tpa, avg_ftm, avg_fta, avg_bpm_rd, avg_obpm, avg_dbpm, avg_bpm_net, avg_pts, avg_orb, avg_drb, avg_ast, avg_tov, avg_stl, avg_blk, avg_stl_per, avg_blk_per, avg_pf, avg_possessions, avg_bpm, avg_sbpm, avg_inches, avg_opstyle, avg_quality, avg_win1, avg_win2
-        FROM stats.player_season_avg_stats WHERE team = ? AND year = ? ALLOW FILTERING
-    "#;
-
-    let prepared_avg = match db.prepare(query_cql_avg).await {
-        Ok(stmt) => stmt,
-        Err(e) => {
-            error!("Failed to prepare query for player season averages: {}", e);
-            return HttpResponse::InternalServerError().body("Failed to prepare query");
-        }
-    };
-
-    let result_avg = db.execute(&prepared_avg, (team_code, year)).await;
-
-    let rows_avg = match result_avg {
-        Ok(res) => res.rows.unwrap_or_default(),
-        Err(e) => {
-            error!("Failed to query player season averages: {}", e);
-            return HttpResponse::InternalServerError().body("Query failed");
-        }
-    };
-
-    let mut player_averages = Vec::new();
-    for (i, row) in rows_avg.into_iter().enumerate() {
-        match PlayerSeasonAverages::from_row(row) {
-            Ok(avg) => player_averages.push(avg),
-            Err(e) => error!("Failed to parse player season average row {}: {}", i, e),
-        }
-    }
-
-    // Fetch percentiles from database
-    let query_cql_pct = r#"
-        SELECT pid, year, team, player_name, pct_min_per, pct_o_rtg, pct_usg, pct_e_fg, pct_ts_per, pct_orb_per, pct_drb_per, pct_ast_per, pct_to_per, pct_dunks_made, pct_dunks_att, pct_rim_made, pct_rim_att, pct_mid_made, pct_mid_att, pct_two_pm, pct_two_pa, pct_tpm, pct_tpa, pct_ftm, pct_fta, pct_bpm_rd, pct_obpm, pct_dbpm, pct_bpm_net, pct_pts, pct_orb, pct_drb, pct_ast, pct_tov, pct_stl, pct_blk, pct_stl_per, pct_blk_per, pct_pf, pct_possessions, pct_bpm, pct_sbpm, pct_inches, pct_opstyle, pct_quality, pct_win1, pct_win2
-        FROM stats.player_season_percentiles WHERE team = ? AND year = ? ALLOW FILTERING
-    "#;
-
-    let prepared_pct = match db.prepare(query_cql_pct).await {
-        Ok(stmt) => stmt,
-        Err(e) => {
-            error!("Failed to prepare query for player season percentiles: {}", e);
-            return HttpResponse::InternalServerError().body("Failed to prepare query");
-        }
-    };
-
-    let result_pct = db.execute(&prepared_pct, (team_code, year)).await;
-
-    let rows_pct = match result_pct {
-        Ok(res) => res.rows.unwrap_or_default(),
-        Err(e) => {
-            error!("Failed to query player season percentiles: {}", e);
-            return HttpResponse::InternalServerError().body("Query failed");
-        }
-    };
-
-    let mut player_percentiles = Vec::new();
-    for (i, row) in rows_pct.into_iter().enumerate() {
-        match PlayerSeasonPercentiles::from_row(row) {
-            Ok(pct) => player_percentiles.push(pct),
-            Err(e) => error!("Failed to parse player season percentile row {}: {}", i, e),
-        }
-    }
+    // Averages and percentiles come from the same snapshot, so a refresh in progress
+    // never pairs new averages with old percentiles.
+    let snapshot = analytics.current();
 
     // Create a HashMap for quick lookup of percentiles by pid
     let percentiles_map: std::collections::HashMap<i32, &PlayerSeasonPercentiles> =
-        player_percentiles.iter().map(|p| (p.pid, p)).collect();
+        snapshot.percentiles(team_code, year).iter().map(|p| (p.pid, p)).collect();
 
     // Combine averages and percentiles
     let mut combined_stats = Vec::new();
-    for avg in player_averages {
+    for avg in snapshot.averages(team_code, year) {
         if let Some(pct) = percentiles_map.get(&avg.pid) {
             combined_stats.push(PlayerStatsWithPercentiles {
                 pid: avg.pid,
@@ -696,73 +765,467 @@ async fn get_player_stats_with_percentiles_endpoint(
     HttpResponse::Ok().json(combined_stats)
 }
 
+// How one stat of a player's (`pid`) or team's (`team`) ratings moved over a season,
+// from the daily snapshots taken at each load
+#[get("/api/history")]
+async fn get_history_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
+    };
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    env_logger::init();
-    init_db().await.expect("DB setup failed");
+    let stat = match query.get("stat") {
+        Some(s) => s,
+        None => return HttpResponse::BadRequest().body("Missing 'stat' query param"),
+    };
 
-    let db = connect_to_scylla().await;
+    let points = match (query.get("pid"), query.get("team")) {
+        (Some(p), _) => {
+            let pid = match p.parse::<i32>() {
+                Ok(n) => n,
+                Err(_) => return HttpResponse::BadRequest().body("Invalid 'pid' query param"),
+            };
+            match store.player_history(pid, year).await {
+                Ok(snapshots) => trajectory(
+                    snapshots.iter().map(|s| (s.snapshot_date.as_str(), s.team.as_str(), s.stats.as_str())),
+                    stat,
+                ),
+                Err(e) => {
+                    error!("Failed to query player history: {}", e);
+                    return HttpResponse::InternalServerError().body("Query failed");
+                }
+            }
+        }
+        (None, Some(team)) => match store.team_history(&canonical_team(store.get_ref(), team).await, year).await {
+            Ok(snapshots) => trajectory(
+                snapshots.iter().map(|s| (s.snapshot_date.as_str(), s.team.as_str(), s.stats.as_str())),
+                stat,
+            ),
+            Err(e) => {
+                error!("Failed to query team history: {}", e);
+                return HttpResponse::InternalServerError().body("Query failed");
+            }
+        },
+        (None, None) => return HttpResponse::BadRequest().body("Missing 'pid' or 'team' query param"),
+    };
+
+    match points {
+        Some(points) => HttpResponse::Ok().json(points),
+        None => HttpResponse::BadRequest().body(format!("Unknown stat '{}'", stat)),
+    }
+}
 
-    info!("🚀 Server running at http://localhost:8000");
+// Players who changed teams going into a season, with their BPM, usage and efficiency
+// before and after the move. `team` narrows to moves into or out of one team.
+#[get("/api/transfers")]
+async fn get_transfers_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => return HttpResponse::BadRequest().body("Missing 'year' query param"),
+    };
 
-    // Set this to `true` to skip fetching and inserting data for faster testing.
-    // Ensure your ScyllaDB instance already has data if you set this to `true`.
-    const SKIP_DATA_LOADING: bool = true; // Set to `true` to skip data loading
+    let team = match query.get("team") {
+        Some(t) => Some(canonical_team(store.get_ref(), t).await),
+        None => None,
+    };
 
-    #[allow(unused_assignments)] // Suppress warning about game_stats being assigned but not directly read after assignment
-    let mut game_stats: Vec<GameStats> = Vec::new(); // Declare game_stats mutably outside the if block
+    let mut transfers = match store.transfers(year).await {
+        Ok(t) => t,
+        Err(e) => {
+            error!("Failed to query transfers: {}", e);
+            return HttpResponse::InternalServerError().body("Query failed");
+        }
+    };
 
-    if !SKIP_DATA_LOADING {
-        let players: Vec<PlayerStats> = get_player_data().await?;
-        info!("Players collected: {}", players.len());
-        insert_player_stats(&db, &players).await?;
+    if let Some(team) = &team {
+        transfers.retain(|t| &t.from_team == team || &t.to_team == team);
+    }
+    // Biggest BPM gain first; moves missing either season's BPM go last.
+    transfers.sort_by(|a, b| match (a.bpm_delta, b.bpm_delta) {
+        (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
 
-        query_specific_player(&db, "Duke", "Cooper Flagg", 2025).await?;
+    HttpResponse::Ok().json(transfers)
+}
 
-        let team_stats = get_team_stats().await?;
-        info!("Inserting {} team stats into ScyllaDB", team_stats.len());
-        insert_team_stats(&db, &team_stats).await?;
+// Every conference's averages, barthag spread and non-conference SOS for a season,
+// strongest (by average barthag) first
+#[get("/api/conferences")]
+async fn get_conferences_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => current_season(),
+    };
 
-        game_stats = get_game_data().await?; // Assign to the outer game_stats
-        info!("Inserting {} game stats into ScyllaDB", game_stats.len());
-        insert_game_stats(&db, &game_stats).await?;
-    } else {
-        info!("Skipping initial data loading and insertion as SKIP_DATA_LOADING is true.");
-        // Fetch game_stats from DB when skipping initial loading, so analytics can still run.
-        game_stats = get_all_game_stats_from_db(&db).await?;
+    match store.conferences(year).await {
+        Ok(mut conferences) => {
+            conferences.sort_by_key(|c| c.rank);
+            HttpResponse::Ok().json(conferences)
+        }
+        Err(e) => {
+            error!("Failed to query conferences: {}", e);
+            HttpResponse::InternalServerError().body("Query failed")
+        }
     }
+}
 
+// One conference's summary and standings for a season. `conf` is matched ignoring case.
+#[get("/api/conferences/{conf}")]
+async fn get_conference_endpoint(
+    store: web::Data<dyn StatsStore>,
+    conf: web::Path<String>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let year = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => n,
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => current_season(),
+    };
 
-    // Calculate and insert player season averages
-    info!("Starting player season average calculation...");
-    calculate_and_insert_season_averages(&db, &game_stats).await?;
-    info!("Finished player season average calculation.");
+    let summary = match store.conferences(year).await {
+        Ok(conferences) => conferences.into_iter().find(|c| c.conf.eq_ignore_ascii_case(&conf)),
+        Err(e) => {
+            error!("Failed to query conferences: {}", e);
+            return HttpResponse::InternalServerError().body("Query failed");
+        }
+    };
+    let Some(summary) = summary else {
+        return HttpResponse::NotFound().body(format!("No conference '{}' in {}", conf, year));
+    };
 
-    // Calculate and insert player season percentiles
-    info!("Starting player season percentile calculation...");
-    // Fetch averages for percentile calculation
-    let all_season_averages = get_all_player_season_averages_from_db(&db).await?;
-    calculate_and_insert_season_percentiles(&db, &all_season_averages).await?;
-    info!("Finished player season percentile calculation.");
+    match store.conference_standings(&summary.conf, year).await {
+        Ok(mut standings) => {
+            standings.sort_by_key(|s| s.conf_rank);
+            HttpResponse::Ok().json(ConferenceTable { summary, standings })
+        }
+        Err(e) => {
+            error!("Failed to query conference standings: {}", e);
+            HttpResponse::InternalServerError().body("Query failed")
+        }
+    }
+}
 
+// Admin: what each ingest run loaded and which rows it rejected, for one UTC day.
+#[get("/api/admin/ingest-runs")]
+async fn get_ingest_runs_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let run_date = match query.get("date") {
+        Some(d) => match chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d") {
+            Ok(date) => date.format("%Y-%m-%d").to_string(),
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'date' query param (expected YYYY-MM-DD)"),
+        },
+        None => Utc::now().format("%Y-%m-%d").to_string(),
+    };
 
-    let db_data = web::Data::new(db);
+    let year_filter = match query.get("year") {
+        Some(y) => match y.parse::<i32>() {
+            Ok(n) => Some(n),
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'year' query param"),
+        },
+        None => None,
+    };
+
+    let mut runs = match store.ingest_runs(&run_date).await {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to query ingest runs: {}", e);
+            return HttpResponse::InternalServerError().body("Query failed");
+        }
+    };
+
+    runs.retain(|run| {
+        query.get("source").is_none_or(|s| &run.source == s)
+            && query.get("status").is_none_or(|s| &run.status == s)
+            && year_filter.is_none_or(|y| run.year == y)
+    });
+    runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+
+    HttpResponse::Ok().json(runs)
+}
 
+// Admin: every row a feed rejected for a season, across the runs of one UTC day.
+#[get("/api/admin/ingest-runs/rejected-rows")]
+async fn get_ingest_rejected_rows_endpoint(
+    store: web::Data<dyn StatsStore>,
+    query: web::Query<std::collections::HashMap<String, String>>,
+) -> impl Responder {
+    let run_date = match query.get("date") {
+        Some(d) => match chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d") {
+            Ok(date) => date.format("%Y-%m-%d").to_string(),
+            Err(_) => return HttpResponse::BadRequest().body("Invalid 'date' query param (expected YYYY-MM-DD)"),
+        },
+        None => Utc::now().format("%Y-%m-%d").to_string(),
+    };
+
+    let Some(source) = query.get("source") else {
+        return HttpResponse::BadRequest().body("Missing 'source' query param");
+    };
+
+    let year = match query.get("year").map(|y| y.parse::<i32>()) {
+        Some(Ok(n)) => n,
+        _ => return HttpResponse::BadRequest().body("Missing or invalid 'year' query param"),
+    };
+
+    let mut rows = match store.ingest_rejected_rows(&run_date, source, year).await {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Failed to query rejected rows: {}", e);
+            return HttpResponse::InternalServerError().body("Query failed");
+        }
+    };
+
+    rows.retain(|row| query.get("started_at").is_none_or(|s| &row.started_at == s));
+    rows.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(a.row_index.cmp(&b.row_index)));
+
+    HttpResponse::Ok().json(rows)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    if let Command::Migrate(args) = &cli.command {
+        return migrate(&cli, args).await;
+    }
+    let store = open_store(&cli).await?;
+
+    match cli.command {
+        Command::Ingest(args) => {
+            run_ingest(store.as_ref(), &args.options()).await?;
+            if args.compute {
+                recompute_season_analytics(store.as_ref(), Some(&args.season.0)).await?;
+            }
+        }
+        Command::Compute(args) => {
+            let seasons = args.season.as_ref().map(|s| s.0.as_slice());
+            recompute_season_analytics(store.as_ref(), seasons).await?;
+            refresh_transfers(store.as_ref(), seasons).await?;
+            refresh_conferences(store.as_ref(), seasons).await?;
+        }
+        // An in-memory store starts empty, so the server loads it once before anything else.
+        Command::Serve(args) => serve(store, args, cli.store == StoreKind::Memory).await?,
+        Command::Migrate(_) => unreachable!("handled before the store is opened"),
+    }
+
+    Ok(())
+}
+
+async fn open_store(cli: &Cli) -> Result<Arc<dyn StatsStore>, Box<dyn Error>> {
+    match cli.store {
+        StoreKind::Scylla => {
+            let config = cli.db.config()?;
+            let session = connect_to_scylla(&config).await?;
+            check_schema_version(&session).await?;
+            Ok(Arc::new(ScyllaStore::new(Arc::new(session), &config)))
+        }
+        #[cfg(feature = "sqlite")]
+        StoreKind::Sqlite => Ok(Arc::new(sqlite_store::SqliteStore::open(&cli.sqlite_path)?)),
+        #[cfg(not(feature = "sqlite"))]
+        StoreKind::Sqlite => Err(format!(
+            "Can't open {}: mater was built without SQLite support (rebuild with `--features sqlite`)",
+            cli.sqlite_path.display()
+        ).into()),
+        StoreKind::Memory => {
+            info!("Using the in-memory store; nothing is kept once the process exits");
+            Ok(Arc::new(MemoryStore::default()))
+        }
+    }
+}
+
+async fn migrate(cli: &Cli, args: &MigrateArgs) -> Result<(), Box<dyn Error>> {
+    if cli.store != StoreKind::Scylla {
+        info!("The {:?} store creates its tables when it is opened; there is nothing to migrate", cli.store);
+        return Ok(());
+    }
+    let session = connect_for_migrations(&cli.db.config()?).await?;
+    if !args.status {
+        run_migrations(&session, args.allow_data_loss).await?;
+    }
+    log_migration_status(&session).await
+}
+
+async fn serve(store: Arc<dyn StatsStore>, args: ServeArgs, initial_refresh: bool) -> Result<(), Box<dyn Error>> {
+    let analytics = web::Data::new(SharedAnalytics::new(AnalyticsSnapshot::load(store.as_ref()).await?));
+    let store_data: web::Data<dyn StatsStore> = web::Data::from(store.clone());
+    let (bind, port) = (args.bind.clone(), args.port);
+
+    // Only this process writes to an in-memory store, and its refreshes swap the snapshot.
+    if !initial_refresh && args.reload_interval > 0 {
+        let (store, analytics) = (store.clone(), analytics.clone());
+        let interval = std::time::Duration::from_secs(args.reload_interval * 60);
+        tokio::spawn(async move { run_snapshot_reloads(store.as_ref(), &analytics, interval).await });
+    }
+
+    if initial_refresh || args.refresh_schedule.is_some() {
+        let analytics = analytics.clone();
+        tokio::spawn(async move {
+            if initial_refresh && let Err(e) = refresh(store.as_ref(), &analytics, &args.refresh_options()).await {
+                error!("Initial refresh failed: {}", e);
+            }
+            if let Some(schedule) = args.refresh_schedule.clone() {
+                info!("Scheduled refreshes enabled: '{}'", schedule);
+                run_scheduled_refreshes(store.as_ref(), &analytics, schedule, || args.refresh_options()).await;
+            }
+        });
+    }
+
+    info!("🚀 Server running at http://{}:{}", bind, port);
     HttpServer::new(move || {
         App::new()
-            .app_data(db_data.clone())
+            .app_data(store_data.clone())
+            .app_data(analytics.clone())
             .service(get_players_endpoint)
+            .service(get_player_endpoint)
             .service(get_team_stats_endpoint)
+            .service(get_teams_endpoint)
+            .service(get_team_results_endpoint)
             .service(get_game_stats_endpoint)
+            .service(get_schedule_game_endpoint)
+            .service(get_player_shot_profiles_endpoint)
             .service(get_player_season_averages_endpoint)
             .service(get_player_rolling_averages_endpoint)
             .service(get_player_stats_with_percentiles_endpoint)
+            .service(get_history_endpoint)
+            .service(get_transfers_endpoint)
+            .service(get_conferences_endpoint)
+            .service(get_conference_endpoint)
+            .service(get_ingest_runs_endpoint)
+            .service(get_ingest_rejected_rows_endpoint)
             .service(hello)
     })
-        .bind(("0.0.0.0", 8000))?
+        .bind((bind.as_str(), port))?
         .run()
-        .await.expect("Failed to start API listener");
-
+        .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::StatusCode;
+    use serde_json::Value;
+    use crate::ingest::tests::load_fixtures;
+
+    macro_rules! fixture_app {
+        () => {{
+            let store: Arc<dyn StatsStore> = Arc::new(load_fixtures().await);
+            let analytics = SharedAnalytics::new(AnalyticsSnapshot::load(store.as_ref()).await.unwrap());
+            test::init_service(
+                App::new()
+                    .app_data(web::Data::from(store))
+                    .app_data(web::Data::new(analytics))
+                    .service(get_players_endpoint)
+                    .service(get_team_stats_endpoint)
+                    .service(get_player_season_averages_endpoint)
+                    .service(get_player_stats_with_percentiles_endpoint)
+                    .service(get_transfers_endpoint)
+                    .service(get_conferences_endpoint)
+                    .service(get_conference_endpoint),
+            )
+            .await
+        }};
+    }
+
+    macro_rules! get_json {
+        ($app:expr, $uri:expr) => {{
+            let resp = test::call_service(&$app, test::TestRequest::get().uri($uri).to_request()).await;
+            assert_eq!(resp.status(), StatusCode::OK, "GET {}", $uri);
+            let body: Value = test::read_body_json(resp).await;
+            body
+        }};
+    }
+
+    fn names(rows: &Value, field: &str) -> Vec<String> {
+        rows.as_array().unwrap().iter().map(|r| r[field].as_str().unwrap().to_string()).collect()
+    }
+
+    #[actix_web::test]
+    async fn players_by_team_sorted_by_minutes() {
+        let app = fixture_app!();
+        let players = get_json!(app, "/api/players?team=North%20Carolina&year=2026");
+        assert_eq!(names(&players, "player_name"), ["Jane Doe", "Sam Roe"]);
+
+        let seasons = get_json!(app, "/api/players?pid=4242&year=2025");
+        assert_eq!(names(&seasons, "team"), ["Duke"]);
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/api/players?team=Duke").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn team_stats_sorted_by_rank_with_season() {
+        let app = fixture_app!();
+        let teams = get_json!(app, "/api/team-stats?year=2026");
+        assert_eq!(names(&teams, "team"), ["Duke", "North Carolina", "Kansas", "Texas Tech", "Iowa St."]);
+        assert!(teams.as_array().unwrap().iter().all(|t| t["year"] == 2026));
+    }
+
+    #[actix_web::test]
+    async fn season_averages_and_percentiles_come_from_the_snapshot() {
+        let app = fixture_app!();
+        let averages = get_json!(app, "/api/player-season-averages?team=North%20Carolina&year=2026");
+        let jane = averages.as_array().unwrap().iter().find(|a| a["pid"] == 4242).unwrap();
+        assert_eq!(jane["games_played"], 3);
+        assert_eq!(jane["avg_pts"], 20.0);
+
+        // Max Poe (Duke) outscores both North Carolina players, so ranks above them.
+        let duke = get_json!(app, "/api/player-stats-with-percentiles?team=Duke&year=2026");
+        let unc = get_json!(app, "/api/player-stats-with-percentiles?team=North%20Carolina&year=2026");
+        let duke = duke.as_array().unwrap();
+        assert_eq!(duke.len(), 1);
+        assert_eq!(duke[0]["avg_pts"], 30.0);
+        let top = duke[0]["pct_pts"].as_f64().unwrap();
+        assert!(unc.as_array().unwrap().iter().all(|p| p["pct_pts"].as_f64().unwrap() < top));
+    }
+
+    #[actix_web::test]
+    async fn transfers_filter_by_either_team() {
+        let app = fixture_app!();
+        for team in ["Duke", "North%20Carolina"] {
+            let transfers = get_json!(app, &format!("/api/transfers?year=2026&team={}", team));
+            assert_eq!(names(&transfers, "player_name"), ["Jane Doe"]);
+        }
+        let transfers = get_json!(app, "/api/transfers?year=2026&team=Kansas");
+        assert!(transfers.as_array().unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn conferences_ranked_with_standings() {
+        let app = fixture_app!();
+        let conferences = get_json!(app, "/api/conferences?year=2026");
+        assert_eq!(names(&conferences, "conf"), ["ACC", "B12"]);
+        assert_eq!(conferences[0]["leader"], "North Carolina");
+
+        let b12 = get_json!(app, "/api/conferences/b12?year=2026");
+        assert_eq!(b12["teams"], 3);
+        assert_eq!(names(&b12["standings"], "team"), ["Kansas", "Iowa St.", "Texas Tech"]);
+
+        let resp = test::call_service(&app, test::TestRequest::get().uri("/api/conferences/SEC?year=2026").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,384 @@
+// src/memory_store.rs
+use std::collections::{BTreeMap, HashSet};
+use std::hash::Hash;
+use std::sync::RwLock;
+use std::time::Instant;
+use async_trait::async_trait;
+
+use crate::analytics_types::{PlayerSeasonAverages, PlayerSeasonPercentiles};
+use crate::batch_writer::WriteReport;
+use crate::get_four_factors::TeamFourFactors;
+use crate::get_game_stats::{GameKey, GameStats};
+use crate::get_player_shots::PlayerShotProfile;
+use crate::get_player_stats::PlayerStats;
+use crate::get_schedule::ScheduleGame;
+use crate::get_team_results::TeamGameResult;
+use crate::get_team_shooting::TeamShooting;
+use crate::get_team_stats::TeamStats;
+use crate::ingest_report::{IngestRejectedRow, IngestRun};
+use crate::player_registry::PlayerRecord;
+use crate::rating_history::{PlayerSnapshot, TeamSnapshot};
+use crate::team_registry::{TeamRecord, team_key};
+use crate::transfers::Transfer;
+use crate::conferences::{ConferenceStanding, ConferenceSummary};
+use crate::stats_store::{StatsStore, StoreResult};
+
+type Table<K, T> = RwLock<BTreeMap<K, T>>;
+
+/// `StatsStore` that keeps everything in memory, for local runs without a database and
+/// for exercising the API and analytics. Rows are keyed by the same primary keys as
+/// the Scylla tables, so upserts behave the same way. Nothing survives a restart.
+#[derive(Default)]
+pub struct MemoryStore {
+    // (team, year, player_name)
+    players: Table<(String, Option<i32>, String), PlayerStats>,
+    // (year, team)
+    teams: Table<(i32, String), TeamStats>,
+    games: Table<GameKey, GameStats>,
+    // (team, year, pid)
+    averages: Table<(String, i32, i32), PlayerSeasonAverages>,
+    // (pid, year, team)
+    percentiles: Table<(i32, i32, String), PlayerSeasonPercentiles>,
+    // (muid, year)
+    schedule: Table<(String, i32), ScheduleGame>,
+    // (year, team)
+    four_factors: Table<(i32, String), TeamFourFactors>,
+    // (team, year, pid)
+    shot_profiles: Table<(String, i32, i32), PlayerShotProfile>,
+    // (year, team)
+    team_shooting: Table<(i32, String), TeamShooting>,
+    // (team, year, numdate)
+    team_results: Table<(String, i32, String), TeamGameResult>,
+    // (pid, year, snapshot_date, team)
+    player_history: Table<(i32, i32, String, String), PlayerSnapshot>,
+    // (team, year, snapshot_date)
+    team_history: Table<(String, i32, String), TeamSnapshot>,
+    // pid
+    player_records: Table<i32, PlayerRecord>,
+    // team_id
+    team_records: Table<String, TeamRecord>,
+    // (year, pid)
+    transfers: Table<(i32, i32), Transfer>,
+    // (year, conf)
+    conferences: Table<(i32, String), ConferenceSummary>,
+    // (year, conf, conf_rank)
+    conference_standings: Table<(i32, String, i32), ConferenceStanding>,
+    // (run_date, started_at, source, year)
+    ingest_runs: Table<(String, String, String, i32), IngestRun>,
+    // (run_date, source, year, started_at, row_index)
+    ingest_rejected_rows: Table<(String, String, i32, String, i32), IngestRejectedRow>,
+}
+
+// Upserts `rows` into `table` and reports it like a batched Scylla write, counting
+// partitions by `partition_key`.
+fn upsert<K: Ord, P: Hash + Eq, T: Clone>(
+    table: &Table<K, T>,
+    name: &str,
+    rows: &[T],
+    key: impl Fn(&T) -> K,
+    partition_key: impl Fn(&T) -> P,
+) -> WriteReport {
+    let started = Instant::now();
+    let mut table = table.write().unwrap();
+    for row in rows {
+        table.insert(key(row), row.clone());
+    }
+    WriteReport {
+        table: name.to_string(),
+        rows: rows.len(),
+        partitions: rows.iter().map(partition_key).collect::<HashSet<_>>().len(),
+        requests: 0,
+        elapsed: started.elapsed(),
+    }
+}
+
+fn delete<K: Ord, T>(table: &Table<K, T>, filter: impl Fn(&T) -> bool) {
+    table.write().unwrap().retain(|_, row| !filter(row));
+}
+
+fn select<K, T: Clone>(table: &Table<K, T>, filter: impl Fn(&T) -> bool) -> Vec<T> {
+    table.read().unwrap().values().filter(|row| filter(row)).cloned().collect()
+}
+
+#[async_trait]
+impl StatsStore for MemoryStore {
+    fn name(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn persistent(&self) -> bool {
+        false
+    }
+
+    async fn players(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerStats>> {
+        Ok(select(&self.players, |p| p.team == team && p.year == Some(year)))
+    }
+
+    async fn player_seasons(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerStats>> {
+        Ok(select(&self.players, |p| p.pid == Some(pid) && p.year == Some(year)))
+    }
+
+    async fn write_players(&self, players: &[PlayerStats]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.players,
+            "player_stats",
+            players,
+            |p| (p.team.clone(), p.year, p.player_name.clone()),
+            |p| (p.team.clone(), p.year),
+        ))
+    }
+
+    async fn team_stats(&self, year: i32) -> StoreResult<Vec<TeamStats>> {
+        Ok(select(&self.teams, |t| t.year == year))
+    }
+
+    async fn write_team_stats(&self, teams: &[TeamStats]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.teams, "team_stats", teams, |t| (t.year, t.team.clone()), |t| t.year))
+    }
+
+    async fn player_games(&self, pid: i32, year: i32, team: &str) -> StoreResult<Vec<GameStats>> {
+        Ok(select(&self.games, |g| g.pid == Some(pid) && g.year == Some(year) && g.tt == team))
+    }
+
+    async fn team_games(&self, team: &str, year: i32) -> StoreResult<Vec<GameStats>> {
+        Ok(select(&self.games, |g| g.tt == team && g.year == Some(year)))
+    }
+
+    async fn season_games(&self, year: i32) -> StoreResult<Vec<GameStats>> {
+        Ok(select(&self.games, |g| g.year == Some(year)))
+    }
+
+    async fn all_games(&self) -> StoreResult<Vec<GameStats>> {
+        Ok(select(&self.games, |_| true))
+    }
+
+    async fn write_games(&self, games: &[GameStats]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.games, "game_stats", games, GameStats::key, |g| (g.pid, g.year, g.tt.clone())))
+    }
+
+    async fn season_averages(&self) -> StoreResult<Vec<PlayerSeasonAverages>> {
+        Ok(select(&self.averages, |_| true))
+    }
+
+    async fn write_season_averages(&self, averages: &[PlayerSeasonAverages]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.averages,
+            "player_season_avg_stats",
+            averages,
+            |a| (a.team.clone(), a.year, a.pid),
+            |a| (a.team.clone(), a.year),
+        ))
+    }
+
+    async fn season_percentiles(&self) -> StoreResult<Vec<PlayerSeasonPercentiles>> {
+        Ok(select(&self.percentiles, |_| true))
+    }
+
+    async fn write_season_percentiles(&self, percentiles: &[PlayerSeasonPercentiles]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.percentiles,
+            "player_season_percentiles",
+            percentiles,
+            |p| (p.pid, p.year, p.team.clone()),
+            |p| (p.pid, p.year),
+        ))
+    }
+
+    async fn schedule_game(&self, muid: &str, year: i32) -> StoreResult<Option<ScheduleGame>> {
+        Ok(self.schedule.read().unwrap().get(&(muid.to_string(), year)).cloned())
+    }
+
+    async fn write_schedule_games(&self, games: &[ScheduleGame]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.schedule, "schedule_games", games, |g| (g.muid.clone(), g.year), |g| (g.muid.clone(), g.year)))
+    }
+
+    async fn four_factors(&self, year: i32) -> StoreResult<Vec<TeamFourFactors>> {
+        Ok(select(&self.four_factors, |f| f.year == year))
+    }
+
+    async fn write_four_factors(&self, teams: &[TeamFourFactors]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.four_factors, "team_four_factors", teams, |f| (f.year, f.team.clone()), |f| f.year))
+    }
+
+    async fn player_shot_profiles(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerShotProfile>> {
+        Ok(select(&self.shot_profiles, |p| p.team == team && p.year == year))
+    }
+
+    async fn write_player_shot_profiles(&self, profiles: &[PlayerShotProfile]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.shot_profiles,
+            "player_shot_profiles",
+            profiles,
+            |p| (p.team.clone(), p.year, p.pid),
+            |p| (p.team.clone(), p.year),
+        ))
+    }
+
+    async fn team_shooting(&self, year: i32) -> StoreResult<Vec<TeamShooting>> {
+        Ok(select(&self.team_shooting, |s| s.year == year))
+    }
+
+    async fn write_team_shooting(&self, teams: &[TeamShooting]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.team_shooting, "team_shooting", teams, |s| (s.year, s.team.clone()), |s| s.year))
+    }
+
+    async fn team_results(&self, team: &str, year: i32) -> StoreResult<Vec<TeamGameResult>> {
+        Ok(select(&self.team_results, |r| r.team == team && r.year == year))
+    }
+
+    async fn write_team_results(&self, results: &[TeamGameResult]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.team_results,
+            "team_results",
+            results,
+            |r| (r.team.clone(), r.year, r.numdate.clone()),
+            |r| (r.team.clone(), r.year),
+        ))
+    }
+
+    async fn player_history(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerSnapshot>> {
+        Ok(select(&self.player_history, |s| s.pid == pid && s.year == year))
+    }
+
+    async fn write_player_history(&self, snapshots: &[PlayerSnapshot]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.player_history,
+            "player_stats_history",
+            snapshots,
+            |s| (s.pid, s.year, s.snapshot_date.clone(), s.team.clone()),
+            |s| (s.pid, s.year),
+        ))
+    }
+
+    async fn team_history(&self, team: &str, year: i32) -> StoreResult<Vec<TeamSnapshot>> {
+        Ok(select(&self.team_history, |s| s.team == team && s.year == year))
+    }
+
+    async fn write_team_history(&self, snapshots: &[TeamSnapshot]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.team_history,
+            "team_stats_history",
+            snapshots,
+            |s| (s.team.clone(), s.year, s.snapshot_date.clone()),
+            |s| (s.team.clone(), s.year),
+        ))
+    }
+
+    async fn player_record(&self, pid: i32) -> StoreResult<Option<PlayerRecord>> {
+        Ok(self.player_records.read().unwrap().get(&pid).cloned())
+    }
+
+    async fn all_player_records(&self) -> StoreResult<Vec<PlayerRecord>> {
+        Ok(select(&self.player_records, |_| true))
+    }
+
+    async fn write_player_records(&self, records: &[PlayerRecord]) -> StoreResult<WriteReport> {
+        let started = Instant::now();
+        let mut stored = self.player_records.write().unwrap();
+        for record in records {
+            stored.entry(record.pid).or_insert_with(|| PlayerRecord { pid: record.pid, ..Default::default() }).merge(record.clone());
+        }
+        Ok(WriteReport {
+            table: "players".to_string(),
+            rows: records.len(),
+            partitions: records.len(),
+            requests: 0,
+            elapsed: started.elapsed(),
+        })
+    }
+
+    async fn teams(&self) -> StoreResult<Vec<TeamRecord>> {
+        Ok(select(&self.team_records, |_| true))
+    }
+
+    async fn write_teams(&self, teams: &[TeamRecord]) -> StoreResult<WriteReport> {
+        let started = Instant::now();
+        let mut stored = self.team_records.write().unwrap();
+        for team in teams {
+            match stored.get_mut(&team.team_id) {
+                Some(existing) => existing.merge(team.clone()),
+                None => {
+                    stored.insert(team.team_id.clone(), team.clone());
+                }
+            }
+        }
+        Ok(WriteReport {
+            table: "teams".to_string(),
+            rows: teams.len(),
+            partitions: teams.len(),
+            requests: 0,
+            elapsed: started.elapsed(),
+        })
+    }
+
+    async fn team_name(&self, alias: &str) -> StoreResult<Option<String>> {
+        let key = team_key(alias);
+        let teams = self.team_records.read().unwrap();
+        Ok(teams.values().find(|t| t.spellings().any(|s| team_key(s) == key)).map(|t| t.name.clone()))
+    }
+
+    async fn transfers(&self, year: i32) -> StoreResult<Vec<Transfer>> {
+        Ok(select(&self.transfers, |t| t.year == year))
+    }
+
+    async fn write_transfers(&self, transfers: &[Transfer]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.transfers, "transfers", transfers, |t| (t.year, t.pid), |t| t.year))
+    }
+
+    async fn clear_transfers(&self, year: i32) -> StoreResult<()> {
+        delete(&self.transfers, |t| t.year == year);
+        Ok(())
+    }
+
+    async fn conferences(&self, year: i32) -> StoreResult<Vec<ConferenceSummary>> {
+        Ok(select(&self.conferences, |c| c.year == year))
+    }
+
+    async fn write_conferences(&self, summaries: &[ConferenceSummary]) -> StoreResult<WriteReport> {
+        Ok(upsert(&self.conferences, "conference_stats", summaries, |c| (c.year, c.conf.clone()), |c| c.year))
+    }
+
+    async fn conference_standings(&self, conf: &str, year: i32) -> StoreResult<Vec<ConferenceStanding>> {
+        Ok(select(&self.conference_standings, |s| s.year == year && s.conf == conf))
+    }
+
+    async fn write_conference_standings(&self, standings: &[ConferenceStanding]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.conference_standings,
+            "conference_standings",
+            standings,
+            |s| (s.year, s.conf.clone(), s.conf_rank),
+            |s| (s.year, s.conf.clone()),
+        ))
+    }
+
+    async fn clear_conferences(&self, year: i32) -> StoreResult<()> {
+        delete(&self.conferences, |c| c.year == year);
+        delete(&self.conference_standings, |s| s.year == year);
+        Ok(())
+    }
+
+    async fn ingest_runs(&self, run_date: &str) -> StoreResult<Vec<IngestRun>> {
+        Ok(select(&self.ingest_runs, |r| r.run_date == run_date))
+    }
+
+    async fn write_ingest_run(&self, run: &IngestRun) -> StoreResult<()> {
+        let key = (run.run_date.clone(), run.started_at.clone(), run.source.clone(), run.year);
+        self.ingest_runs.write().unwrap().insert(key, run.clone());
+        Ok(())
+    }
+
+    async fn ingest_rejected_rows(&self, run_date: &str, source: &str, year: i32) -> StoreResult<Vec<IngestRejectedRow>> {
+        Ok(select(&self.ingest_rejected_rows, |r| r.run_date == run_date && r.source == source && r.year == year))
+    }
+
+    async fn write_ingest_rejected_rows(&self, rows: &[IngestRejectedRow]) -> StoreResult<WriteReport> {
+        Ok(upsert(
+            &self.ingest_rejected_rows,
+            "ingest_rejected_rows",
+            rows,
+            |r| (r.run_date.clone(), r.source.clone(), r.year, r.started_at.clone(), r.row_index),
+            |r| (r.run_date.clone(), r.source.clone(), r.year),
+        ))
+    }
+}
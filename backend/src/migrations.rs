@@ -0,0 +1,224 @@
+// src/migrations.rs
+use std::collections::BTreeMap;
+use std::error::Error;
+use log::{info, warn};
+use scylla::Session;
+
+use crate::db_config::DbConfig;
+use crate::db_utils::connect_to_cluster;
+
+/// A numbered schema change, applied once and recorded in `schema_version`.
+struct Migration {
+    version: i32,
+    name: &'static str,
+    cql: &'static str,
+    /// Tables whose rows, or columns of them, the migration discards; it only runs over rows
+    /// that exist with `--allow-data-loss`.
+    drops: &'static [&'static str],
+}
+
+/// Every migration, in order. To change the schema, add the next numbered file under
+/// `migrations/` and list it here; never edit a migration that has shipped. Table names
+/// are left unqualified: migrations run in the configured keyspace.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "initial_schema", cql: include_str!("../migrations/0001_initial_schema.cql"), drops: &[] },
+    Migration { version: 2, name: "query_tables", cql: include_str!("../migrations/0002_query_tables.cql"), drops: &[] },
+    Migration { version: 3, name: "rating_history", cql: include_str!("../migrations/0003_rating_history.cql"), drops: &[] },
+    Migration { version: 4, name: "player_registry", cql: include_str!("../migrations/0004_player_registry.cql"), drops: &[] },
+    Migration { version: 5, name: "team_registry", cql: include_str!("../migrations/0005_team_registry.cql"), drops: &[] },
+    Migration { version: 6, name: "transfers", cql: include_str!("../migrations/0006_transfers.cql"), drops: &[] },
+    Migration { version: 7, name: "conferences", cql: include_str!("../migrations/0007_conferences.cql"), drops: &[] },
+    Migration { version: 8, name: "team_stats_by_year", cql: include_str!("../migrations/0008_team_stats_by_year.cql"), drops: &["team_stats"] },
+];
+
+/// The schema version this build reads and writes.
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+impl Migration {
+    // Splits the file into statements, dropping `--` comment lines.
+    fn statements(&self) -> Vec<String> {
+        let cql: Vec<&str> = self.cql.lines().filter(|l| !l.trim_start().starts_with("--")).collect();
+        cql.join("\n")
+            .split(';')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Connects to the cluster, creating the configured keyspace (with the configured
+/// replication) and the `schema_version` table if they are missing, so migrations can run
+/// on an empty cluster. An existing keyspace keeps its replication.
+pub async fn connect_for_migrations(config: &DbConfig) -> Result<Session, Box<dyn Error>> {
+    let session = connect_to_cluster(config).await?;
+
+    info!("Creating keyspace '{}' if missing, with replication {}", config.keyspace, config.replication);
+    session
+        .query(
+            format!(
+                "CREATE KEYSPACE IF NOT EXISTS \"{}\" WITH replication = {};",
+                config.keyspace,
+                config.replication.to_cql()
+            ),
+            &[],
+        )
+        .await?;
+    session.use_keyspace(&config.keyspace, true).await?;
+
+    session
+        .query(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version int,
+                name text,
+                applied_at text,
+                PRIMARY KEY (version)
+            );",
+            &[],
+        )
+        .await?;
+    Ok(session)
+}
+
+/// Applied migrations by version, with the name and time each was applied.
+async fn applied_migrations(session: &Session) -> Result<BTreeMap<i32, (String, String)>, Box<dyn Error>> {
+    // A handful of rows, so reading the whole table is fine.
+    let result = session.query("SELECT version, name, applied_at FROM schema_version", &[]).await?;
+    let mut applied = BTreeMap::new();
+    for row in result.rows_typed::<(i32, String, String)>()? {
+        let (version, name, applied_at) = row?;
+        applied.insert(version, (name, applied_at));
+    }
+    Ok(applied)
+}
+
+// Refuses a migration that would discard data in tables which still hold rows.
+async fn check_no_data_loss(session: &Session, migration: &Migration) -> Result<(), Box<dyn Error>> {
+    let mut populated = Vec::new();
+    for &table in migration.drops {
+        let result = session.query(format!("SELECT * FROM {} LIMIT 1", table), &[]).await?;
+        if result.rows.is_some_and(|rows| !rows.is_empty()) {
+            populated.push(table);
+        }
+    }
+    if populated.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Migration {:04} {} would discard data in {}. Run `mater migrate --allow-data-loss` to apply it, \
+         then reload it with `mater ingest --force` (the README's table notes name the feeds)",
+        migration.version,
+        migration.name,
+        populated.join(", ")
+    ).into())
+}
+
+/// Applies every pending migration in order, recording each in `schema_version` once all
+/// of its statements have run.
+///
+/// Statements run one by one and Scylla schema changes aren't transactional, so a
+/// migration that fails partway is left half applied and unrecorded; fix the cause and
+/// run it again (which is why statements should be safe to repeat where CQL allows).
+///
+/// A migration that discards table data stops before running while the tables hold
+/// rows, unless `allow_data_loss` is set; the error names the tables and how to reload
+/// them.
+pub async fn run_migrations(session: &Session, allow_data_loss: bool) -> Result<(), Box<dyn Error>> {
+    let applied = applied_migrations(session).await?;
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| !applied.contains_key(&m.version)).collect();
+    if pending.is_empty() {
+        info!("Schema is up to date at version {}", latest_version());
+        return Ok(());
+    }
+
+    for migration in pending {
+        if !allow_data_loss {
+            check_no_data_loss(session, migration).await?;
+        }
+        info!("Applying migration {:04} {}", migration.version, migration.name);
+        for statement in migration.statements() {
+            session.query(statement.as_str(), &[]).await.map_err(|e| {
+                format!("Migration {:04} {} failed: {}", migration.version, migration.name, e)
+            })?;
+        }
+        session
+            .query(
+                "INSERT INTO schema_version (version, name, applied_at) VALUES (?, ?, ?)",
+                (migration.version, migration.name, chrono::Utc::now().to_rfc3339()),
+            )
+            .await?;
+    }
+    info!("✅ Schema migrated to version {}", latest_version());
+    Ok(())
+}
+
+/// Logs every known migration and whether it has been applied.
+pub async fn log_migration_status(session: &Session) -> Result<(), Box<dyn Error>> {
+    let applied = applied_migrations(session).await?;
+    for migration in MIGRATIONS {
+        match applied.get(&migration.version) {
+            Some((_, applied_at)) => info!("{:04} {:<24} applied {}", migration.version, migration.name, applied_at),
+            None => info!("{:04} {:<24} pending", migration.version, migration.name),
+        }
+    }
+    for (version, (name, _)) in applied.range(latest_version() + 1..) {
+        warn!("{:04} {:<24} applied by a newer build", version, name);
+    }
+    Ok(())
+}
+
+/// Refuses to run against a schema this build hasn't migrated to yet. A schema that is
+/// ahead (migrated by a newer build) is only warned about.
+pub async fn check_schema_version(session: &Session) -> Result<(), Box<dyn Error>> {
+    let applied = applied_migrations(session)
+        .await
+        .map_err(|e| format!("Failed to read schema_version ({}); run `mater migrate` first", e))?;
+    let pending: Vec<i32> = MIGRATIONS.iter().map(|m| m.version).filter(|v| !applied.contains_key(v)).collect();
+    if !pending.is_empty() {
+        return Err(format!(
+            "Schema is missing migration(s) {:?} (this build expects version {}); run `mater migrate` first",
+            pending,
+            latest_version()
+        ).into());
+    }
+    if let Some(&newest) = applied.keys().next_back()
+        && newest > latest_version()
+    {
+        warn!("Schema is at version {}, newer than this build's {}", newest, latest_version());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_consecutive_from_one() {
+        let versions: Vec<i32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(versions, (1..=latest_version()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn every_dropped_table_is_guarded() {
+        for migration in MIGRATIONS {
+            for statement in migration.statements() {
+                if let Some(table) = statement.strip_prefix("DROP TABLE IF EXISTS ") {
+                    assert!(migration.drops.contains(&table.trim()), "{:04} drops {} unguarded", migration.version, table);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn statements_run_in_the_session_keyspace() {
+        for migration in MIGRATIONS {
+            for statement in migration.statements() {
+                assert!(!statement.contains("stats."), "{:04} qualifies a table: {}", migration.version, statement);
+                assert!(!statement.contains("--"), "{:04} kept a comment: {}", migration.version, statement);
+            }
+        }
+    }
+}
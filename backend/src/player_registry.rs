@@ -0,0 +1,230 @@
+// src/player_registry.rs
+use std::collections::{BTreeMap, BTreeSet};
+use log::error;
+use serde::{Deserialize, Serialize};
+use futures_util::stream::StreamExt;
+use scylla::Session;
+use scylla::query::Query;
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::get_player_stats::PlayerStats;
+
+type BySeason = BTreeMap<i32, String>;
+type TeamsBySeason = BTreeMap<i32, BTreeSet<String>>;
+
+/// Everything mater knows about one player across teams and seasons, as stored in the
+/// `players` registry. Each field is keyed by season, so loading any season merges into
+/// the record rather than replacing it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerRecord {
+    pub pid: i32,
+    /// The name as spelled in each season's feed.
+    pub names: BySeason,
+    /// Every team the player appeared for each season; more than one after a mid-season move.
+    pub teams: TeamsBySeason,
+    /// Listed height (`6-5`), per season.
+    pub heights: BySeason,
+    /// Class (`Fr`, `So`, ...), per season.
+    pub classes: BySeason,
+    /// No Barttorvik feed carries hometowns, so this is always null for now.
+    pub hometown: Option<String>,
+}
+
+/// A season of a player's career, as served by `/api/player`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerSeason {
+    pub year: i32,
+    pub teams: Vec<String>,
+    pub name: Option<String>,
+    pub height: Option<String>,
+    pub class: Option<String>,
+}
+
+/// A player's canonical identity, as served by `/api/player`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerIdentity {
+    pub pid: i32,
+    /// The name as spelled in the player's latest season.
+    pub name: String,
+    /// Every other spelling seen in earlier seasons.
+    pub aliases: Vec<String>,
+    /// The teams of the player's latest season.
+    pub teams: Vec<String>,
+    pub height: Option<String>,
+    pub class: Option<String>,
+    /// Always null for now; see `PlayerRecord::hometown`.
+    pub hometown: Option<String>,
+    /// Oldest first.
+    pub seasons: Vec<PlayerSeason>,
+}
+
+impl PlayerRecord {
+    /// The registry entry a `player_stats` row contributes, if it has a pid and season.
+    pub fn from_stats(player: &PlayerStats) -> Option<Self> {
+        let (pid, year) = (player.pid?, player.year?);
+        let mut record = PlayerRecord { pid, ..Default::default() };
+        record.names.insert(year, player.player_name.clone());
+        record.teams.insert(year, BTreeSet::from([player.team.clone()]));
+        if let Some(ht) = &player.ht {
+            record.heights.insert(year, ht.clone());
+        }
+        if let Some(yr) = &player.yr {
+            record.classes.insert(year, yr.clone());
+        }
+        Some(record)
+    }
+
+    /// Folds `other` (the same player) into this record; its seasons win where both have one.
+    pub fn merge(&mut self, other: PlayerRecord) {
+        self.names.extend(other.names);
+        self.teams.extend(other.teams);
+        self.heights.extend(other.heights);
+        self.classes.extend(other.classes);
+        if other.hometown.is_some() {
+            self.hometown = other.hometown;
+        }
+    }
+
+    /// The teams the player was on in `year`, if that season was loaded.
+    pub fn teams_in(&self, year: i32) -> Option<&BTreeSet<String>> {
+        self.teams.get(&year).filter(|teams| !teams.is_empty())
+    }
+
+    pub fn identity(&self) -> PlayerIdentity {
+        let latest = |by_season: &BySeason| by_season.values().next_back().cloned();
+        let name = latest(&self.names).unwrap_or_default();
+        let aliases: BTreeSet<&String> = self.names.values().filter(|n| **n != name).collect();
+
+        let years: BTreeSet<i32> = self
+            .names
+            .keys()
+            .chain(self.teams.keys())
+            .chain(self.heights.keys())
+            .chain(self.classes.keys())
+            .copied()
+            .collect();
+        let seasons = years
+            .into_iter()
+            .map(|year| PlayerSeason {
+                year,
+                teams: self.teams.get(&year).map(|t| t.iter().cloned().collect()).unwrap_or_default(),
+                name: self.names.get(&year).cloned(),
+                height: self.heights.get(&year).cloned(),
+                class: self.classes.get(&year).cloned(),
+            })
+            .collect();
+
+        PlayerIdentity {
+            pid: self.pid,
+            aliases: aliases.into_iter().cloned().collect(),
+            name,
+            teams: self.teams.values().next_back().map(|t| t.iter().cloned().collect()).unwrap_or_default(),
+            height: latest(&self.heights),
+            class: latest(&self.classes),
+            hometown: self.hometown.clone(),
+            seasons,
+        }
+    }
+}
+
+/// Registry entries for every `players` row that has a pid, one per player.
+pub fn player_records(players: &[PlayerStats]) -> Vec<PlayerRecord> {
+    let mut records: BTreeMap<i32, PlayerRecord> = BTreeMap::new();
+    for mut record in players.iter().filter_map(PlayerRecord::from_stats) {
+        match records.get_mut(&record.pid) {
+            // One feed row per team, so a player who moved mid-season has a row for each.
+            Some(existing) => {
+                for (year, teams) in std::mem::take(&mut record.teams) {
+                    existing.teams.entry(year).or_default().extend(teams);
+                }
+                existing.merge(record);
+            }
+            None => {
+                records.insert(record.pid, record);
+            }
+        }
+    }
+    records.into_values().collect()
+}
+
+/// Merges `records` into `players`. Collection appends merge server-side, so no read is
+/// needed and reloading a season is idempotent; a season's team set is replaced whole.
+pub async fn insert_player_records(session: &Session, records: &[PlayerRecord]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    UPDATE players SET names = names + ?, teams = teams + ?, heights = heights + ?, classes = classes + ?
+    WHERE pid = ?
+"#;
+    let rows: Vec<_> = records
+        .iter()
+        .map(|r| (&r.names, &r.teams, &r.heights, &r.classes, r.pid))
+        .collect();
+    write_batched(session, "players", query, &rows, |r| r.4).await
+}
+
+/// Fetches the whole registry, one row per player ever loaded.
+pub async fn get_all_player_records_from_db(session: &Session) -> Result<Vec<PlayerRecord>, QueryError> {
+    let mut query = Query::new("SELECT pid, names, teams, heights, classes, hometown FROM players");
+    query.set_page_size(5000);
+
+    let mut rows_iter = session.query_iter(query, ()).await?;
+    let mut records = Vec::new();
+    while let Some(row) = rows_iter.next().await {
+        match row?.into_typed::<RecordRow>() {
+            Ok(row) => records.push(record_from_row(row)),
+            Err(e) => error!("Player registry row failed to convert: {}", e),
+        }
+    }
+    Ok(records)
+}
+
+/// Fetches a player's registry entry.
+pub async fn get_player_record_from_db(session: &Session, pid: i32) -> Result<Option<PlayerRecord>, QueryError> {
+    let query = "SELECT pid, names, teams, heights, classes, hometown FROM players WHERE pid = ?";
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (pid,)).await?;
+    Ok(result.rows.unwrap_or_default().into_iter().find_map(|row| match row.into_typed::<RecordRow>() {
+        Ok(row) => Some(record_from_row(row)),
+        Err(e) => {
+            error!("Player registry row for {} failed to convert: {}", pid, e);
+            None
+        }
+    }))
+}
+
+// Empty collections read back as null.
+type RecordRow = (i32, Option<BySeason>, Option<TeamsBySeason>, Option<BySeason>, Option<BySeason>, Option<String>);
+
+fn record_from_row((pid, names, teams, heights, classes, hometown): RecordRow) -> PlayerRecord {
+    PlayerRecord {
+        pid,
+        names: names.unwrap_or_default(),
+        teams: teams.unwrap_or_default(),
+        heights: heights.unwrap_or_default(),
+        classes: classes.unwrap_or_default(),
+        hometown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(team: &str, year: i32) -> PlayerStats {
+        let row = format!(r#"{{"player_name":"Jane Doe","team":"{}","conf":"ACC","pid":4242,"year":{}}}"#, team, year);
+        serde_json::from_str(&row).unwrap()
+    }
+
+    #[test]
+    fn keeps_every_team_of_a_season() {
+        let records = player_records(&[player("Duke", 2025), player("Duke", 2026), player("North Carolina", 2026)]);
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.teams_in(2025), Some(&BTreeSet::from(["Duke".to_string()])));
+        assert_eq!(record.teams_in(2026), Some(&BTreeSet::from(["Duke".to_string(), "North Carolina".to_string()])));
+        assert_eq!(record.hometown, None);
+
+        let round_trip: PlayerRecord = serde_json::from_str(&serde_json::to_string(record).unwrap()).unwrap();
+        assert_eq!(&round_trip, record);
+    }
+}
@@ -0,0 +1,225 @@
+// src/rating_history.rs
+use chrono::{DateTime, Utc};
+use log::error;
+use serde::{Deserialize, Serialize};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::cli::season_on;
+use crate::get_player_stats::PlayerStats;
+use crate::get_team_stats::TeamStats;
+
+/// A player's `player_stats` row as loaded on one day (`player_stats_history`).
+/// `player_stats` is overwritten by every load; these rows keep the season's trajectory.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct PlayerSnapshot {
+    pub pid: i32,
+    pub year: i32,
+    /// UTC day of the load (YYYY-MM-DD); a second load on the same day replaces the first.
+    pub snapshot_date: String,
+    pub team: String,
+    /// The whole `PlayerStats` row as JSON text.
+    pub stats: String,
+}
+
+/// A team's `team_stats` row as loaded on one day (`team_stats_history`).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct TeamSnapshot {
+    pub team: String,
+    pub year: i32,
+    /// UTC day of the load (YYYY-MM-DD).
+    pub snapshot_date: String,
+    /// The whole `TeamStats` row as JSON text.
+    pub stats: String,
+}
+
+/// One point of a stat's trajectory, as served by `/api/history`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPoint {
+    pub snapshot_date: String,
+    pub team: String,
+    /// The stat as loaded that day; null when the feed had no value.
+    pub value: serde_json::Value,
+}
+
+/// The date a load of `season` at `now` is snapshotted under, while that season is the
+/// one being played. A past season's feed shows how the season ended, not how it stood on
+/// the day it was loaded, so back-filling one records no history.
+pub fn snapshot_date(season: i32, now: DateTime<Utc>) -> Option<String> {
+    (season_on(now) == season).then(|| now.format("%Y-%m-%d").to_string())
+}
+
+/// Snapshots of `players` for `date`. Rows without a pid or season can't be keyed and are skipped.
+pub fn player_snapshots(players: &[PlayerStats], date: &str) -> Result<Vec<PlayerSnapshot>, serde_json::Error> {
+    let mut snapshots = Vec::with_capacity(players.len());
+    for player in players {
+        let (Some(pid), Some(year)) = (player.pid, player.year) else { continue };
+        snapshots.push(PlayerSnapshot {
+            pid,
+            year,
+            snapshot_date: date.to_string(),
+            team: player.team.clone(),
+            stats: serde_json::to_string(player)?,
+        });
+    }
+    Ok(snapshots)
+}
+
+/// Snapshots of `teams` for `date`.
+pub fn team_snapshots(teams: &[TeamStats], date: &str) -> Result<Vec<TeamSnapshot>, serde_json::Error> {
+    teams
+        .iter()
+        .map(|team| {
+            Ok(TeamSnapshot {
+                team: team.team.clone(),
+                year: team.year,
+                snapshot_date: date.to_string(),
+                stats: serde_json::to_string(team)?,
+            })
+        })
+        .collect()
+}
+
+/// Picks `stat` out of each snapshot's stored row, oldest first. Returns `None` when no
+/// snapshot has a field by that name, so callers can tell a typo from an empty history.
+pub fn trajectory<'a>(
+    snapshots: impl Iterator<Item = (&'a str, &'a str, &'a str)>,
+    stat: &str,
+) -> Option<Vec<HistoryPoint>> {
+    let mut points = Vec::new();
+    let mut decoded = 0;
+    for (snapshot_date, team, stats) in snapshots {
+        let row: serde_json::Value = match serde_json::from_str(stats) {
+            Ok(row) => row,
+            Err(e) => {
+                error!("Snapshot for {} on {} failed to decode: {}", team, snapshot_date, e);
+                continue;
+            }
+        };
+        decoded += 1;
+        let Some(value) = row.get(stat) else { continue };
+        points.push(HistoryPoint {
+            snapshot_date: snapshot_date.to_string(),
+            team: team.to_string(),
+            value: value.clone(),
+        });
+    }
+    points.sort_by(|a, b| a.snapshot_date.cmp(&b.snapshot_date).then_with(|| a.team.cmp(&b.team)));
+    (decoded == 0 || !points.is_empty()).then_some(points)
+}
+
+pub async fn insert_player_snapshots(session: &Session, snapshots: &[PlayerSnapshot]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO player_stats_history (pid, year, snapshot_date, team, stats) VALUES (?, ?, ?, ?, ?)
+"#;
+    write_batched(session, "player_stats_history", query, snapshots, |s| (s.pid, s.year)).await
+}
+
+pub async fn insert_team_snapshots(session: &Session, snapshots: &[TeamSnapshot]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO team_stats_history (team, year, snapshot_date, stats) VALUES (?, ?, ?, ?)
+"#;
+    write_batched(session, "team_stats_history", query, snapshots, |s| (s.team.clone(), s.year)).await
+}
+
+/// Fetches every daily snapshot of a player's season (one partition).
+pub async fn get_player_snapshots_from_db(session: &Session, pid: i32, year: i32) -> Result<Vec<PlayerSnapshot>, QueryError> {
+    let query = r#"
+    SELECT pid, year, snapshot_date, team, stats FROM player_stats_history WHERE pid = ? AND year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (pid, year)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut snapshots = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match PlayerSnapshot::from_row(row) {
+            Ok(s) => snapshots.push(s),
+            Err(e) => error!("Player snapshot row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(snapshots)
+}
+
+/// Fetches every daily snapshot of a team's season (one partition).
+pub async fn get_team_snapshots_from_db(session: &Session, team: &str, year: i32) -> Result<Vec<TeamSnapshot>, QueryError> {
+    let query = r#"
+    SELECT team, year, snapshot_date, stats FROM team_stats_history WHERE team = ? AND year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (team, year)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut snapshots = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match TeamSnapshot::from_row(row) {
+            Ok(s) => snapshots.push(s),
+            Err(e) => error!("Team snapshot row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::ingest::tests::load_fixtures;
+    use crate::stats_store::StatsStore;
+
+    #[test]
+    fn only_the_season_being_played_is_snapshotted() {
+        let march = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(snapshot_date(2026, march).as_deref(), Some("2026-03-01"));
+        assert_eq!(snapshot_date(2025, march), None);
+
+        let november = Utc.with_ymd_and_hms(2026, 11, 20, 12, 0, 0).unwrap();
+        assert_eq!(snapshot_date(2027, november).as_deref(), Some("2026-11-20"));
+        assert_eq!(snapshot_date(2026, november), None);
+    }
+
+    #[tokio::test]
+    async fn back_filled_seasons_record_no_history() {
+        let store = load_fixtures().await;
+        assert!(!store.players("Duke", 2025).await.unwrap().is_empty());
+        assert!(store.player_history(4242, 2025).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshots_keep_whole_rows_and_trace_a_stat() {
+        let store = load_fixtures().await;
+        let players = store.players("North Carolina", 2026).await.unwrap();
+        let mut later = players.clone();
+        later.iter_mut().for_each(|p| p.bpm = p.bpm.map(|bpm| bpm + 1.0));
+
+        let mut snapshots = player_snapshots(&later, "2026-03-01").unwrap();
+        snapshots.extend(player_snapshots(&players, "2026-02-01").unwrap());
+        let jane: Vec<&PlayerSnapshot> = snapshots.iter().filter(|s| s.pid == 4242).collect();
+        assert_eq!(jane.len(), 2);
+        let stored: serde_json::Value = serde_json::from_str(&jane[1].stats).unwrap();
+        let original = players.iter().find(|p| p.pid == Some(4242)).unwrap();
+        assert_eq!(stored, serde_json::to_value(original).unwrap());
+
+        let points = trajectory(jane.iter().map(|s| (s.snapshot_date.as_str(), s.team.as_str(), s.stats.as_str())), "bpm")
+            .unwrap();
+        let dates: Vec<&str> = points.iter().map(|p| p.snapshot_date.as_str()).collect();
+        assert_eq!(dates, ["2026-02-01", "2026-03-01"]);
+        let bpm = |i: usize| points[i].value.as_f64().unwrap();
+        assert!((bpm(1) - bpm(0) - 1.0).abs() < 1e-9);
+
+        let rows = jane.iter().map(|s| (s.snapshot_date.as_str(), s.team.as_str(), s.stats.as_str()));
+        assert!(trajectory(rows, "not_a_stat").is_none());
+        assert_eq!(trajectory(std::iter::empty(), "bpm").map(|p| p.len()), Some(0));
+
+        let teams = store.team_stats(2026).await.unwrap();
+        let team_rows = team_snapshots(&teams, "2026-03-01").unwrap();
+        assert_eq!(team_rows.len(), teams.len());
+        let points = trajectory(team_rows.iter().map(|s| (s.snapshot_date.as_str(), s.team.as_str(), s.stats.as_str())), "barthag")
+            .unwrap();
+        assert_eq!(points.len(), teams.len());
+        assert!(points.iter().all(|p| p.value.is_number()));
+    }
+}
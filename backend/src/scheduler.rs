@@ -0,0 +1,310 @@
+// src/scheduler.rs
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use chrono::{DateTime, Datelike, Duration, DurationRound, Timelike, Utc};
+use log::{error, info, warn};
+
+use crate::analytics_snapshot::{AnalyticsSnapshot, SharedAnalytics};
+use crate::ingest::{IngestOptions, run_ingest};
+use crate::stats_store::StatsStore;
+
+/// A cron schedule in the usual five fields, `minute hour day-of-month month day-of-week`,
+/// evaluated in UTC.
+///
+/// Each field takes `*`, a value, a range `a-b`, a step `*/n` or `a-b/n`, or a comma list
+/// of those. Day-of-week runs 0-6 from Sunday (7 is also Sunday). As in cron, when both
+/// day fields are restricted a day matching either one fires. `@hourly` and `@daily` are
+/// accepted as shorthands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    expr: String,
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "Expected 5 fields (minute hour day-of-month month day-of-week) in '{}'",
+                expr
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, "day-of-week", 0, 7)?;
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+        days_of_week.truncate(7);
+
+        Ok(Schedule {
+            expr: expr.trim().to_string(),
+            minutes: parse_field(minute, "minute", 0, 59)?,
+            hours: parse_field(hour, "hour", 0, 23)?,
+            days_of_month: parse_field(day_of_month, "day-of-month", 1, 31)?,
+            months: parse_field(month, "month", 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: day_of_month != "*",
+            day_of_week_restricted: day_of_week != "*",
+        })
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+// Parses one cron field into a table indexed by value, `true` where the field matches.
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let invalid = || format!("Invalid {} field '{}'", name, field);
+    let mut matches = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (a.parse().map_err(|_| invalid())?, b.parse().map_err(|_| invalid())?),
+                // `a/n` means every n-th value from a to the end of the field.
+                None => {
+                    let a: u32 = range.parse().map_err(|_| invalid())?;
+                    (a, if part.contains('/') { max } else { a })
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            matches[value as usize] = true;
+        }
+    }
+    Ok(matches)
+}
+
+impl Schedule {
+    /// The first minute strictly after `after` that the schedule fires on, or `None` if it
+    /// never fires (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        // Every schedule that can fire does so within a leap-year cycle.
+        let limit = t + Duration::days(4 * 366);
+
+        while t < limit {
+            if !self.months[t.month() as usize] {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = t.with_day(1)?.with_hour(0)?.with_minute(0)?.with_year(year)?.with_month(month)?;
+                continue;
+            }
+            if !self.day_matches(t) {
+                t = t.with_hour(0)?.with_minute(0)? + Duration::days(1);
+                continue;
+            }
+            if !self.hours[t.hour() as usize] {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes[t.minute() as usize] {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let dom = self.days_of_month[t.day() as usize];
+        let dow = self.days_of_week[t.weekday().num_days_from_sunday() as usize];
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+}
+
+/// Runs a refresh every time `schedule` fires, for as long as the server is up.
+///
+/// Refreshes never overlap: the next run is scheduled from when the previous one
+/// finished, so a slot missed while a long refresh was running is skipped.
+pub async fn run_scheduled_refreshes(
+    store: &dyn StatsStore,
+    analytics: &SharedAnalytics,
+    schedule: Schedule,
+    options: impl Fn() -> IngestOptions,
+) {
+    loop {
+        let Some(next) = schedule.next_after(Utc::now()) else {
+            warn!("Refresh schedule '{}' never fires; scheduled refreshes are off", schedule);
+            return;
+        };
+        info!("Next scheduled refresh at {}", next.to_rfc3339());
+        let wait = (next - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        if let Err(e) = refresh(store, analytics, &options()).await {
+            error!("Scheduled refresh failed: {}", e);
+        }
+    }
+}
+
+/// Re-reads the served analytics from the store every `interval`, so averages and
+/// percentiles written by another process (a cron `mater ingest` or `mater compute`)
+/// replace the snapshot without a restart.
+pub async fn run_snapshot_reloads(store: &dyn StatsStore, analytics: &SharedAnalytics, interval: std::time::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    // The first tick fires at once, and the snapshot was only just loaded.
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        if let Err(e) = reload(store, analytics).await {
+            error!("Failed to reload the analytics snapshot: {}", e);
+        }
+    }
+}
+
+// Reads the analytics into a new snapshot and serves it, once any refresh running in this
+// process has finished writing them.
+async fn reload(store: &dyn StatsStore, analytics: &SharedAnalytics) -> Result<(), Box<dyn Error>> {
+    let _updating = analytics.begin_update().await;
+    analytics.replace(AnalyticsSnapshot::load(store).await?);
+    Ok(())
+}
+
+// Loads the feeds, which rebuilds the averages and percentiles of every player season
+// whose games changed, then swaps the served analytics for the new ones in one step.
+// Until the swap, the API keeps serving the previous complete set, never a mix of old
+// and new rows.
+pub async fn refresh(store: &dyn StatsStore, analytics: &SharedAnalytics, options: &IngestOptions) -> Result<(), Box<dyn Error>> {
+    let _updating = analytics.begin_update().await;
+    info!("Starting refresh of seasons {:?}", options.seasons);
+
+    // Feeds that failed are already recorded in ingest_runs; what did load still counts.
+    if let Err(e) = run_ingest(store, options).await {
+        warn!("Ingest finished with errors: {}", e);
+    }
+
+    let snapshot = AnalyticsSnapshot::load(store).await?;
+    info!("Refresh finished; serving analytics loaded at {}", snapshot.loaded_at);
+    analytics.replace(snapshot);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::ingest::tests::load_fixtures;
+    use crate::memory_store::MemoryStore;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        expr.parse::<Schedule>().unwrap().next_after(after)
+    }
+
+    #[test]
+    fn parses_fields_and_shorthands() {
+        let s: Schedule = "0 */3 * 11-12,1-4 *".parse().unwrap();
+        assert!(s.hours[0] && s.hours[3] && s.hours[21] && !s.hours[1]);
+        assert!(s.months[11] && s.months[4] && !s.months[5]);
+        assert!(!s.day_of_month_restricted && !s.day_of_week_restricted);
+        assert_eq!(s.to_string(), "0 */3 * 11-12,1-4 *");
+
+        assert_eq!("@hourly".parse::<Schedule>().unwrap().minutes, "0 * * * *".parse::<Schedule>().unwrap().minutes);
+        let daily: Schedule = "@daily".parse().unwrap();
+        assert!(daily.hours[0] && !daily.hours[1]);
+
+        let sunday: Schedule = "0 0 * * 7".parse().unwrap();
+        assert_eq!(sunday.days_of_week.len(), 7);
+        assert!(sunday.days_of_week[0]);
+
+        let stepped: Schedule = "5/20 * * * *".parse().unwrap();
+        let minutes: Vec<usize> = (0..60).filter(|&m| stepped.minutes[m]).collect();
+        assert_eq!(minutes, vec![5, 25, 45]);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expr in ["", "* * * *", "* * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "* * * 13 *",
+            "* * * * 8", "5-1 * * * *", "*/0 * * * *", "a * * * *"] {
+            assert!(expr.parse::<Schedule>().is_err(), "'{}' should be rejected", expr);
+        }
+    }
+
+    #[test]
+    fn next_after_is_strictly_later_and_minute_aligned() {
+        let after = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 30).unwrap();
+        assert_eq!(next("* * * * *", after), Some(at(2026, 1, 10, 12, 1)));
+        assert_eq!(next("0 * * * *", at(2026, 1, 10, 12, 0)), Some(at(2026, 1, 10, 13, 0)));
+        assert_eq!(next("30 2 * * *", at(2026, 1, 10, 12, 0)), Some(at(2026, 1, 11, 2, 30)));
+    }
+
+    #[test]
+    fn next_after_rolls_over_months_and_years() {
+        // Out of season: the next run is the first slot in November.
+        assert_eq!(next("0 */3 * 11-12,1-4 *", at(2026, 5, 15, 8, 0)), Some(at(2026, 11, 1, 0, 0)));
+        assert_eq!(next("0 0 1 1 *", at(2026, 12, 31, 23, 59)), Some(at(2027, 1, 1, 0, 0)));
+        assert_eq!(next("0 0 29 2 *", at(2026, 3, 1, 0, 0)), Some(at(2028, 2, 29, 0, 0)));
+    }
+
+    #[test]
+    fn next_after_matches_either_restricted_day_field() {
+        // 2026-01-10 is a Saturday: the 15th or any Monday, whichever comes first.
+        assert_eq!(next("0 6 15 * 1", at(2026, 1, 10, 0, 0)), Some(at(2026, 1, 12, 6, 0)));
+        assert_eq!(next("0 6 15 * 1", at(2026, 1, 12, 7, 0)), Some(at(2026, 1, 15, 6, 0)));
+        assert_eq!(next("0 6 * * 0", at(2026, 1, 10, 0, 0)), Some(at(2026, 1, 11, 6, 0)));
+    }
+
+    #[test]
+    fn next_after_is_none_for_impossible_dates() {
+        assert_eq!(next("0 0 30 2 *", at(2026, 1, 1, 0, 0)), None);
+    }
+
+    #[tokio::test]
+    async fn reload_waits_for_a_refresh_to_finish_writing() {
+        let source = load_fixtures().await;
+        let store = MemoryStore::default();
+        let analytics = SharedAnalytics::new(AnalyticsSnapshot::default());
+
+        // A refresh has written the averages but not yet the percentiles.
+        let refreshing = analytics.begin_update().await;
+        store.write_season_averages(&source.season_averages().await.unwrap()).await.unwrap();
+
+        let reloading = reload(&store, &analytics);
+        tokio::pin!(reloading);
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(50), &mut reloading).await.is_err());
+        assert!(analytics.current().averages("North Carolina", 2026).is_empty());
+
+        store.write_season_percentiles(&source.season_percentiles().await.unwrap()).await.unwrap();
+        drop(refreshing);
+        reloading.await.unwrap();
+
+        let snapshot = analytics.current();
+        assert_eq!(snapshot.averages("North Carolina", 2026).len(), 2);
+        assert_eq!(snapshot.percentiles("North Carolina", 2026).len(), 2);
+    }
+}
@@ -0,0 +1,294 @@
+// src/schema_drift.rs
+use std::collections::BTreeMap;
+use csv::StringRecord;
+use log::{info, warn};
+use serde::Serialize;
+
+/// What a feed column is expected to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    /// Non-numeric text such as a player, team or conference name.
+    Text,
+    /// A number, or a string holding one.
+    Number,
+    /// Anything; used for free-form columns like heights and jersey numbers.
+    Any,
+}
+
+/// What a single value in a feed row actually looked like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeenKind {
+    Empty,
+    Number,
+    Text,
+    Other,
+}
+
+impl SeenKind {
+    fn of_str(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() {
+            SeenKind::Empty
+        } else if value.parse::<f64>().is_ok() {
+            SeenKind::Number
+        } else {
+            SeenKind::Text
+        }
+    }
+
+    fn of_json(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => SeenKind::Empty,
+            serde_json::Value::Number(_) => SeenKind::Number,
+            serde_json::Value::String(s) => SeenKind::of_str(s),
+            _ => SeenKind::Other,
+        }
+    }
+
+    // Empty values are always accepted: every optional field may be blank.
+    fn fits(self, expected: ColumnKind) -> bool {
+        match expected {
+            ColumnKind::Any => true,
+            ColumnKind::Number => matches!(self, SeenKind::Empty | SeenKind::Number),
+            ColumnKind::Text => matches!(self, SeenKind::Empty | SeenKind::Text),
+        }
+    }
+}
+
+/// One column whose values didn't match its expected kind.
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnDrift {
+    pub index: usize,
+    pub name: &'static str,
+    pub expected: ColumnKind,
+    /// How many values of each kind were seen in the column.
+    pub seen: BTreeMap<SeenKind, usize>,
+    pub mismatches: usize,
+    /// The first value that didn't match.
+    pub sample: String,
+}
+
+/// How far a feed's rows strayed from the layout its parser expects.
+#[derive(Debug, Clone, Serialize)]
+pub struct DriftReport {
+    pub feed: String,
+    pub rows_checked: usize,
+    /// Rows with the wrong width or at least one mismatched column.
+    pub drifted_rows: usize,
+    pub expected_width: usize,
+    /// Row widths seen, with how many rows had each.
+    pub widths_seen: BTreeMap<usize, usize>,
+    pub columns: Vec<ColumnDrift>,
+}
+
+impl DriftReport {
+    /// Fraction of rows that drifted.
+    pub fn drift_ratio(&self) -> f64 {
+        if self.rows_checked == 0 {
+            0.0
+        } else {
+            self.drifted_rows as f64 / self.rows_checked as f64
+        }
+    }
+
+    /// Logs the report: a single line for a clean feed, every drifted column otherwise.
+    pub fn log(&self) {
+        if self.drifted_rows == 0 {
+            info!("{}: {} rows match the expected {}-column layout", self.feed, self.rows_checked, self.expected_width);
+            return;
+        }
+
+        warn!(
+            "{}: {} of {} rows ({:.1}%) drifted from the expected layout; widths seen: {:?} (expected {})",
+            self.feed, self.drifted_rows, self.rows_checked, self.drift_ratio() * 100.0,
+            self.widths_seen, self.expected_width
+        );
+        for c in &self.columns {
+            warn!(
+                "{}: column {} '{}' expected {:?}, {} mismatches (seen {:?}, e.g. {:?})",
+                self.feed, c.index, c.name, c.expected, c.mismatches, c.seen, c.sample
+            );
+        }
+    }
+
+    /// Fails when more than `max_ratio` of the rows drifted, so a shifted feed never
+    /// overwrites good data.
+    pub fn check(&self, max_ratio: f64) -> Result<(), String> {
+        if self.drift_ratio() > max_ratio {
+            return Err(format!(
+                "{} drifted in {:.1}% of rows (limit {:.1}%); refusing to write. Drift report: {}",
+                self.feed,
+                self.drift_ratio() * 100.0,
+                max_ratio * 100.0,
+                serde_json::to_string(self).unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct ColumnTally {
+    seen: BTreeMap<SeenKind, usize>,
+    mismatches: usize,
+    sample: Option<String>,
+}
+
+/// Checks feed rows against a positional layout while a parser walks them.
+///
+/// Columns are `Number` unless listed as `text` or `any`.
+pub struct DriftDetector {
+    feed: String,
+    columns: Vec<(&'static str, ColumnKind)>,
+    allow_extra_columns: bool,
+    rows_checked: usize,
+    drifted_rows: usize,
+    widths_seen: BTreeMap<usize, usize>,
+    tallies: Vec<ColumnTally>,
+}
+
+impl DriftDetector {
+    pub fn new(feed: &str, columns: &[&'static str], text: &[&str], any: &[&str]) -> Self {
+        let columns: Vec<(&'static str, ColumnKind)> = columns
+            .iter()
+            .map(|&name| {
+                let kind = if text.contains(&name) {
+                    ColumnKind::Text
+                } else if any.contains(&name) {
+                    ColumnKind::Any
+                } else {
+                    ColumnKind::Number
+                };
+                (name, kind)
+            })
+            .collect();
+
+        DriftDetector {
+            feed: feed.to_string(),
+            tallies: columns.iter().map(|_| ColumnTally::default()).collect(),
+            columns,
+            allow_extra_columns: false,
+            rows_checked: 0,
+            drifted_rows: 0,
+            widths_seen: BTreeMap::new(),
+        }
+    }
+
+    /// Accepts rows with trailing columns beyond the known layout.
+    pub fn allow_extra_columns(mut self) -> Self {
+        self.allow_extra_columns = true;
+        self
+    }
+
+    pub fn check_json_row(&mut self, row: &[serde_json::Value]) {
+        self.check_row(row.len(), |i| {
+            row.get(i).map(|v| (SeenKind::of_json(v), v.to_string()))
+        });
+    }
+
+    /// Checks a JSON feed row of any shape: positional arrays by index, objects by column
+    /// name (their width is how many known columns they have), anything else as an empty
+    /// row.
+    pub fn check_json_value(&mut self, row: &serde_json::Value) {
+        match row {
+            serde_json::Value::Array(values) => self.check_json_row(values),
+            serde_json::Value::Object(fields) => {
+                let names: Vec<&'static str> = self.columns.iter().map(|&(name, _)| name).collect();
+                let width = names.iter().filter(|name| fields.contains_key(**name)).count();
+                self.check_row(width, |i| {
+                    fields.get(names[i]).map(|v| (SeenKind::of_json(v), v.to_string()))
+                });
+            }
+            _ => self.check_row(0, |_| None),
+        }
+    }
+
+    pub fn check_csv_record(&mut self, record: &StringRecord) {
+        self.check_row(record.len(), |i| {
+            record.get(i).map(|v| (SeenKind::of_str(v), v.to_string()))
+        });
+    }
+
+    fn check_row(&mut self, width: usize, value_at: impl Fn(usize) -> Option<(SeenKind, String)>) {
+        self.rows_checked += 1;
+        *self.widths_seen.entry(width).or_default() += 1;
+
+        let expected = self.columns.len();
+        let mut drifted = width < expected || (width > expected && !self.allow_extra_columns);
+
+        for (i, (&(_, kind), tally)) in self.columns.iter().zip(self.tallies.iter_mut()).enumerate() {
+            let Some((seen, raw)) = value_at(i) else { continue };
+            *tally.seen.entry(seen).or_default() += 1;
+            if !seen.fits(kind) {
+                drifted = true;
+                tally.mismatches += 1;
+                tally.sample.get_or_insert(raw);
+            }
+        }
+
+        if drifted {
+            self.drifted_rows += 1;
+        }
+    }
+
+    pub fn report(self) -> DriftReport {
+        let columns = self.columns
+            .iter()
+            .zip(self.tallies)
+            .enumerate()
+            .filter(|(_, (_, tally))| tally.mismatches > 0)
+            .map(|(index, (&(name, expected), tally))| ColumnDrift {
+                index,
+                name,
+                expected,
+                seen: tally.seen,
+                mismatches: tally.mismatches,
+                sample: tally.sample.unwrap_or_default(),
+            })
+            .collect();
+
+        DriftReport {
+            feed: self.feed,
+            rows_checked: self.rows_checked,
+            drifted_rows: self.drifted_rows,
+            expected_width: self.columns.len(),
+            widths_seen: self.widths_seen,
+            columns,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn object_rows_are_checked_by_column_name() {
+        let mut drift = DriftDetector::new("teams", &["team", "adjoe", "rank"], &["team"], &[]);
+        drift.check_json_value(&json!({"team": "Duke", "adjoe": 120.5, "rank": 1}));
+        drift.check_json_value(&json!({"team": "Duke", "adjoe": "n/a"}));
+        drift.check_json_value(&json!("Duke"));
+
+        let report = drift.report();
+        assert_eq!((report.rows_checked, report.drifted_rows), (3, 2));
+        assert_eq!(report.widths_seen, BTreeMap::from([(0, 1), (2, 1), (3, 1)]));
+        assert_eq!(report.columns.len(), 1);
+        assert_eq!((report.columns[0].name, report.columns[0].sample.as_str()), ("adjoe", "\"n/a\""));
+    }
+
+    #[test]
+    fn check_allows_drift_up_to_the_limit() {
+        let mut drift = DriftDetector::new("teams", &["team", "adjoe"], &["team"], &[]);
+        for row in [json!(["Duke", 120.5]), json!(["Kansas", 118.0]), json!(["Texas Tech", 115.2]), json!(["Iowa St."])] {
+            drift.check_json_value(&row);
+        }
+        let report = drift.report();
+        assert!(report.check(0.25).is_ok());
+        assert!(report.check(1.0).is_ok());
+        assert!(report.check(0.2).unwrap_err().contains("refusing to write"));
+        assert!(report.check(0.0).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+// src/scylla_store.rs
+use std::sync::Arc;
+use async_trait::async_trait;
+use scylla::Session;
+
+use crate::db_config::DbConfig;
+
+use crate::analytics_calculator::{
+    get_all_player_season_averages_from_db, get_all_player_season_percentiles_from_db,
+    insert_season_averages, insert_season_percentiles,
+};
+use crate::analytics_types::{PlayerSeasonAverages, PlayerSeasonPercentiles};
+use crate::batch_writer::WriteReport;
+use crate::db_utils::{get_player_seasons_from_db, get_players_from_db};
+use crate::get_game_stats::{
+    GameStats, get_all_game_stats_from_db, get_player_game_stats_from_db, get_season_game_stats_from_db,
+    get_team_game_stats_from_db, insert_game_stats,
+};
+use crate::get_four_factors::{TeamFourFactors, get_four_factors_from_db, insert_four_factors};
+use crate::get_player_shots::{PlayerShotProfile, get_player_shot_profiles_from_db, insert_player_shot_profiles};
+use crate::get_player_stats::{PlayerStats, insert_player_stats};
+use crate::get_schedule::{ScheduleGame, get_schedule_game_from_db, insert_schedule_games};
+use crate::get_team_results::{TeamGameResult, get_team_results_from_db, insert_team_results};
+use crate::get_team_shooting::{TeamShooting, get_team_shooting_from_db, insert_team_shooting};
+use crate::get_team_stats::{TeamStats, get_team_stats_from_db, insert_team_stats};
+use crate::ingest_report::{
+    IngestRejectedRow, IngestRun, get_ingest_rejected_rows_from_db, get_ingest_runs_from_db,
+    insert_ingest_rejected_rows, insert_ingest_run,
+};
+use crate::player_registry::{
+    PlayerRecord, get_all_player_records_from_db, get_player_record_from_db, insert_player_records,
+};
+use crate::rating_history::{
+    PlayerSnapshot, TeamSnapshot, get_player_snapshots_from_db, get_team_snapshots_from_db, insert_player_snapshots,
+    insert_team_snapshots,
+};
+use crate::team_registry::{TeamRecord, get_team_name_from_db, get_teams_from_db, insert_teams};
+use crate::conferences::{
+    ConferenceStanding, ConferenceSummary, delete_conferences, get_conference_standings_from_db,
+    get_conferences_from_db, insert_conference_standings, insert_conferences,
+};
+use crate::transfers::{Transfer, delete_transfers, get_transfers_from_db, insert_transfers};
+use crate::stats_store::{StatsStore, StoreResult};
+
+/// `StatsStore` backed by the ScyllaDB tables created by `mater migrate`.
+pub struct ScyllaStore {
+    session: Arc<Session>,
+    name: String,
+}
+
+impl ScyllaStore {
+    /// Wraps a session connected with `config`, which names the cluster and keyspace.
+    pub fn new(session: Arc<Session>, config: &DbConfig) -> Self {
+        let mut hosts = config.hosts.clone();
+        hosts.sort();
+        ScyllaStore { session, name: format!("scylla:{}@{}", config.keyspace, hosts.join(",")) }
+    }
+}
+
+#[async_trait]
+impl StatsStore for ScyllaStore {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn players(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerStats>> {
+        Ok(get_players_from_db(&self.session, team, year).await?)
+    }
+
+    async fn player_seasons(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerStats>> {
+        Ok(get_player_seasons_from_db(&self.session, pid, year).await?)
+    }
+
+    async fn write_players(&self, players: &[PlayerStats]) -> StoreResult<WriteReport> {
+        Ok(insert_player_stats(&self.session, players).await?)
+    }
+
+    async fn team_stats(&self, year: i32) -> StoreResult<Vec<TeamStats>> {
+        Ok(get_team_stats_from_db(&self.session, year).await?)
+    }
+
+    async fn write_team_stats(&self, teams: &[TeamStats]) -> StoreResult<WriteReport> {
+        Ok(insert_team_stats(&self.session, teams).await?)
+    }
+
+    async fn player_games(&self, pid: i32, year: i32, team: &str) -> StoreResult<Vec<GameStats>> {
+        Ok(get_player_game_stats_from_db(&self.session, pid, year, team).await?)
+    }
+
+    async fn team_games(&self, team: &str, year: i32) -> StoreResult<Vec<GameStats>> {
+        Ok(get_team_game_stats_from_db(&self.session, team, year).await?)
+    }
+
+    async fn season_games(&self, year: i32) -> StoreResult<Vec<GameStats>> {
+        Ok(get_season_game_stats_from_db(&self.session, year).await?)
+    }
+
+    async fn all_games(&self) -> StoreResult<Vec<GameStats>> {
+        Ok(get_all_game_stats_from_db(&self.session).await?)
+    }
+
+    async fn write_games(&self, games: &[GameStats]) -> StoreResult<WriteReport> {
+        Ok(insert_game_stats(&self.session, games).await?)
+    }
+
+    async fn season_averages(&self) -> StoreResult<Vec<PlayerSeasonAverages>> {
+        Ok(get_all_player_season_averages_from_db(&self.session).await?)
+    }
+
+    async fn write_season_averages(&self, averages: &[PlayerSeasonAverages]) -> StoreResult<WriteReport> {
+        Ok(insert_season_averages(&self.session, averages).await?)
+    }
+
+    async fn season_percentiles(&self) -> StoreResult<Vec<PlayerSeasonPercentiles>> {
+        Ok(get_all_player_season_percentiles_from_db(&self.session).await?)
+    }
+
+    async fn write_season_percentiles(&self, percentiles: &[PlayerSeasonPercentiles]) -> StoreResult<WriteReport> {
+        Ok(insert_season_percentiles(&self.session, percentiles).await?)
+    }
+
+    async fn schedule_game(&self, muid: &str, year: i32) -> StoreResult<Option<ScheduleGame>> {
+        Ok(get_schedule_game_from_db(&self.session, muid, year).await?)
+    }
+
+    async fn write_schedule_games(&self, games: &[ScheduleGame]) -> StoreResult<WriteReport> {
+        Ok(insert_schedule_games(&self.session, games).await?)
+    }
+
+    async fn four_factors(&self, year: i32) -> StoreResult<Vec<TeamFourFactors>> {
+        Ok(get_four_factors_from_db(&self.session, year).await?)
+    }
+
+    async fn write_four_factors(&self, teams: &[TeamFourFactors]) -> StoreResult<WriteReport> {
+        Ok(insert_four_factors(&self.session, teams).await?)
+    }
+
+    async fn player_shot_profiles(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerShotProfile>> {
+        Ok(get_player_shot_profiles_from_db(&self.session, team, year).await?)
+    }
+
+    async fn write_player_shot_profiles(&self, profiles: &[PlayerShotProfile]) -> StoreResult<WriteReport> {
+        Ok(insert_player_shot_profiles(&self.session, profiles).await?)
+    }
+
+    async fn team_shooting(&self, year: i32) -> StoreResult<Vec<TeamShooting>> {
+        Ok(get_team_shooting_from_db(&self.session, year).await?)
+    }
+
+    async fn write_team_shooting(&self, teams: &[TeamShooting]) -> StoreResult<WriteReport> {
+        Ok(insert_team_shooting(&self.session, teams).await?)
+    }
+
+    async fn team_results(&self, team: &str, year: i32) -> StoreResult<Vec<TeamGameResult>> {
+        Ok(get_team_results_from_db(&self.session, team, year).await?)
+    }
+
+    async fn write_team_results(&self, results: &[TeamGameResult]) -> StoreResult<WriteReport> {
+        Ok(insert_team_results(&self.session, results).await?)
+    }
+
+    async fn player_history(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerSnapshot>> {
+        Ok(get_player_snapshots_from_db(&self.session, pid, year).await?)
+    }
+
+    async fn write_player_history(&self, snapshots: &[PlayerSnapshot]) -> StoreResult<WriteReport> {
+        Ok(insert_player_snapshots(&self.session, snapshots).await?)
+    }
+
+    async fn team_history(&self, team: &str, year: i32) -> StoreResult<Vec<TeamSnapshot>> {
+        Ok(get_team_snapshots_from_db(&self.session, team, year).await?)
+    }
+
+    async fn write_team_history(&self, snapshots: &[TeamSnapshot]) -> StoreResult<WriteReport> {
+        Ok(insert_team_snapshots(&self.session, snapshots).await?)
+    }
+
+    async fn player_record(&self, pid: i32) -> StoreResult<Option<PlayerRecord>> {
+        Ok(get_player_record_from_db(&self.session, pid).await?)
+    }
+
+    async fn all_player_records(&self) -> StoreResult<Vec<PlayerRecord>> {
+        Ok(get_all_player_records_from_db(&self.session).await?)
+    }
+
+    async fn write_player_records(&self, records: &[PlayerRecord]) -> StoreResult<WriteReport> {
+        Ok(insert_player_records(&self.session, records).await?)
+    }
+
+    async fn teams(&self) -> StoreResult<Vec<TeamRecord>> {
+        Ok(get_teams_from_db(&self.session).await?)
+    }
+
+    async fn write_teams(&self, teams: &[TeamRecord]) -> StoreResult<WriteReport> {
+        Ok(insert_teams(&self.session, teams).await?)
+    }
+
+    async fn team_name(&self, alias: &str) -> StoreResult<Option<String>> {
+        Ok(get_team_name_from_db(&self.session, alias).await?)
+    }
+
+    async fn transfers(&self, year: i32) -> StoreResult<Vec<Transfer>> {
+        Ok(get_transfers_from_db(&self.session, year).await?)
+    }
+
+    async fn write_transfers(&self, transfers: &[Transfer]) -> StoreResult<WriteReport> {
+        Ok(insert_transfers(&self.session, transfers).await?)
+    }
+
+    async fn clear_transfers(&self, year: i32) -> StoreResult<()> {
+        Ok(delete_transfers(&self.session, year).await?)
+    }
+
+    async fn conferences(&self, year: i32) -> StoreResult<Vec<ConferenceSummary>> {
+        Ok(get_conferences_from_db(&self.session, year).await?)
+    }
+
+    async fn write_conferences(&self, summaries: &[ConferenceSummary]) -> StoreResult<WriteReport> {
+        Ok(insert_conferences(&self.session, summaries).await?)
+    }
+
+    async fn conference_standings(&self, conf: &str, year: i32) -> StoreResult<Vec<ConferenceStanding>> {
+        Ok(get_conference_standings_from_db(&self.session, conf, year).await?)
+    }
+
+    async fn write_conference_standings(&self, standings: &[ConferenceStanding]) -> StoreResult<WriteReport> {
+        Ok(insert_conference_standings(&self.session, standings).await?)
+    }
+
+    async fn clear_conferences(&self, year: i32) -> StoreResult<()> {
+        Ok(delete_conferences(&self.session, year).await?)
+    }
+
+    async fn ingest_runs(&self, run_date: &str) -> StoreResult<Vec<IngestRun>> {
+        Ok(get_ingest_runs_from_db(&self.session, run_date).await?)
+    }
+
+    async fn write_ingest_run(&self, run: &IngestRun) -> StoreResult<()> {
+        Ok(insert_ingest_run(&self.session, run).await?)
+    }
+
+    async fn ingest_rejected_rows(&self, run_date: &str, source: &str, year: i32) -> StoreResult<Vec<IngestRejectedRow>> {
+        Ok(get_ingest_rejected_rows_from_db(&self.session, run_date, source, year).await?)
+    }
+
+    async fn write_ingest_rejected_rows(&self, rows: &[IngestRejectedRow]) -> StoreResult<WriteReport> {
+        Ok(insert_ingest_rejected_rows(&self.session, rows).await?)
+    }
+}
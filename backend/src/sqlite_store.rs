@@ -0,0 +1,665 @@
+// src/sqlite_store.rs
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use async_trait::async_trait;
+use log::{error, info};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params_from_iter};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::analytics_types::{PlayerSeasonAverages, PlayerSeasonPercentiles};
+use crate::batch_writer::WriteReport;
+use crate::get_four_factors::TeamFourFactors;
+use crate::get_game_stats::GameStats;
+use crate::get_player_shots::PlayerShotProfile;
+use crate::get_player_stats::PlayerStats;
+use crate::get_schedule::ScheduleGame;
+use crate::get_team_results::TeamGameResult;
+use crate::get_team_shooting::TeamShooting;
+use crate::get_team_stats::TeamStats;
+use crate::ingest_report::{IngestRejectedRow, IngestRun};
+use crate::player_registry::PlayerRecord;
+use crate::rating_history::{PlayerSnapshot, TeamSnapshot};
+use crate::team_registry::{TeamAlias, TeamRecord, team_key};
+use crate::transfers::Transfer;
+use crate::conferences::{ConferenceStanding, ConferenceSummary};
+use crate::stats_store::{StatsStore, StoreResult};
+
+/// One SQLite table per Scylla table. Each row is stored whole as JSON in `data`, under
+/// its Scylla primary key (also JSON, since some key columns are nullable), next to
+/// copies of the columns that reads filter on.
+struct Table {
+    name: &'static str,
+    columns: &'static [&'static str],
+    indexes: &'static [&'static [&'static str]],
+}
+
+const TABLES: &[Table] = &[
+    Table { name: "player_stats", columns: &["team", "year"], indexes: &[&["team", "year"]] },
+    Table { name: "player_stats_by_pid", columns: &["pid", "year"], indexes: &[&["pid", "year"]] },
+    Table { name: "team_stats", columns: &["year"], indexes: &[&["year"]] },
+    Table { name: "game_stats", columns: &["pid", "year", "tt"], indexes: &[&["pid", "year", "tt"], &["year", "tt"]] },
+    Table { name: "player_season_avg_stats", columns: &["team", "year"], indexes: &[] },
+    Table { name: "player_season_percentiles", columns: &["pid", "year"], indexes: &[] },
+    Table { name: "schedule_games", columns: &["muid", "year"], indexes: &[&["muid", "year"]] },
+    Table { name: "team_four_factors", columns: &["year"], indexes: &[&["year"]] },
+    Table { name: "team_shooting", columns: &["year"], indexes: &[&["year"]] },
+    Table { name: "team_results", columns: &["team", "year"], indexes: &[&["team", "year"]] },
+    Table { name: "player_shot_profiles", columns: &["team", "year"], indexes: &[&["team", "year"]] },
+    Table { name: "player_stats_history", columns: &["pid", "year"], indexes: &[&["pid", "year"]] },
+    Table { name: "team_stats_history", columns: &["team", "year"], indexes: &[&["team", "year"]] },
+    Table { name: "players", columns: &["pid"], indexes: &[&["pid"]] },
+    Table { name: "teams", columns: &["team_id"], indexes: &[&["team_id"]] },
+    Table { name: "team_aliases", columns: &["alias"], indexes: &[] },
+    Table { name: "transfers", columns: &["year"], indexes: &[&["year"]] },
+    Table { name: "conference_stats", columns: &["year"], indexes: &[&["year"]] },
+    Table { name: "conference_standings", columns: &["conf", "year"], indexes: &[&["conf", "year"]] },
+    Table { name: "ingest_runs", columns: &["run_date"], indexes: &[&["run_date"]] },
+    Table { name: "ingest_rejected_rows", columns: &["run_date", "source", "year"], indexes: &[&["run_date", "source", "year"]] },
+];
+
+/// Most values bound to one `IN (...)` list.
+const MAX_IN_VALUES: usize = 500;
+
+/// `StatsStore` in a single SQLite file, for running mater as one binary without ScyllaDB.
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+    name: String,
+}
+
+impl SqliteStore {
+    /// Opens (or creates) the database file and creates any missing tables.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        info!("Opening SQLite database at {}...", path.display());
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+
+        for table in TABLES {
+            conn.execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {} (key TEXT PRIMARY KEY, {}, data TEXT NOT NULL)",
+                    table.name,
+                    table.columns.join(", ")
+                ),
+                [],
+            )?;
+            for (i, columns) in table.indexes.iter().enumerate() {
+                conn.execute(
+                    &format!(
+                        "CREATE INDEX IF NOT EXISTS {}_by_{} ON {} ({})",
+                        table.name, i, table.name, columns.join(", ")
+                    ),
+                    [],
+                )?;
+            }
+        }
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        Ok(SqliteStore { conn: Arc::new(Mutex::new(conn)), name: format!("sqlite:{}", path.display()) })
+    }
+
+    // Upserts `rows` into `table` in one transaction. `key` is the row's primary key and
+    // `columns` its values for the table's filter columns, in order.
+    async fn upsert<T: Serialize, K: Serialize, P: Hash + Eq>(
+        &self,
+        table: &'static str,
+        rows: &[T],
+        key: impl Fn(&T) -> K,
+        columns: impl Fn(&T) -> Vec<Value>,
+        partition_key: impl Fn(&T) -> P,
+    ) -> StoreResult<WriteReport> {
+        let started = Instant::now();
+        let mut encoded = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut values = vec![Value::Text(serde_json::to_string(&key(row))?)];
+            values.extend(columns(row));
+            values.push(Value::Text(serde_json::to_string(row)?));
+            encoded.push(values);
+        }
+
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || -> rusqlite::Result<()> {
+            let mut conn = conn.lock().unwrap();
+            let tx = conn.transaction()?;
+            {
+                let columns = TABLES.iter().find(|t| t.name == table).expect("known table").columns;
+                let placeholders = vec!["?"; columns.len() + 2].join(", ");
+                let mut insert = tx.prepare(&format!(
+                    "INSERT OR REPLACE INTO {} (key, {}, data) VALUES ({})",
+                    table,
+                    columns.join(", "),
+                    placeholders
+                ))?;
+                for values in encoded {
+                    insert.execute(params_from_iter(values))?;
+                }
+            }
+            tx.commit()
+        })
+        .await
+        .expect("SQLite write task panicked")?;
+
+        let report = WriteReport {
+            table: table.to_string(),
+            rows: rows.len(),
+            partitions: rows.iter().map(partition_key).collect::<HashSet<_>>().len(),
+            requests: 1,
+            elapsed: started.elapsed(),
+        };
+        report.log();
+        Ok(report)
+    }
+
+    // Deletes every row of `table` whose filter columns equal the given values.
+    async fn delete(&self, table: &'static str, filter: Vec<(&'static str, Value)>) -> StoreResult<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || -> rusqlite::Result<usize> {
+            let conn = conn.lock().unwrap();
+            let (columns, values): (Vec<&str>, Vec<Value>) = filter.into_iter().unzip();
+            let conditions: Vec<String> = columns.iter().map(|c| format!("{} = ?", c)).collect();
+            conn.execute(&format!("DELETE FROM {} WHERE {}", table, conditions.join(" AND ")), params_from_iter(values))
+        })
+        .await
+        .expect("SQLite write task panicked")?;
+        Ok(())
+    }
+
+    // Reads every row of `table` whose filter columns equal the given values.
+    async fn select<T: DeserializeOwned + Send + 'static>(
+        &self,
+        table: &'static str,
+        filter: Vec<(&'static str, Value)>,
+    ) -> StoreResult<Vec<T>> {
+        let conn = self.conn.clone();
+        let data = tokio::task::spawn_blocking(move || -> rusqlite::Result<Vec<String>> {
+            let conn = conn.lock().unwrap();
+            let (columns, values): (Vec<&str>, Vec<Value>) = filter.into_iter().unzip();
+            let mut sql = format!("SELECT data FROM {}", table);
+            if !columns.is_empty() {
+                let conditions: Vec<String> = columns.iter().map(|c| format!("{} = ?", c)).collect();
+                sql = format!("{} WHERE {}", sql, conditions.join(" AND "));
+            }
+            let mut select = conn.prepare(&sql)?;
+            let rows = select.query_map(params_from_iter(values), |row| row.get::<_, String>(0))?;
+            rows.collect()
+        })
+        .await
+        .expect("SQLite read task panicked")?;
+
+        Ok(decode(table, &data))
+    }
+
+    // Reads every row of `table` whose `column` is one of `values`, a chunk of values per
+    // query to stay under SQLite's limit on bound parameters.
+    async fn select_in<T: DeserializeOwned + Send + 'static>(
+        &self,
+        table: &'static str,
+        column: &'static str,
+        values: Vec<Value>,
+    ) -> StoreResult<Vec<T>> {
+        let conn = self.conn.clone();
+        let data = tokio::task::spawn_blocking(move || -> rusqlite::Result<Vec<String>> {
+            let conn = conn.lock().unwrap();
+            let mut data = Vec::new();
+            for chunk in values.chunks(MAX_IN_VALUES) {
+                let placeholders = vec!["?"; chunk.len()].join(", ");
+                let mut select = conn.prepare(&format!("SELECT data FROM {} WHERE {} IN ({})", table, column, placeholders))?;
+                let rows = select.query_map(params_from_iter(chunk), |row| row.get::<_, String>(0))?;
+                for row in rows {
+                    data.push(row?);
+                }
+            }
+            Ok(data)
+        })
+        .await
+        .expect("SQLite read task panicked")?;
+
+        Ok(decode(table, &data))
+    }
+}
+
+// Decodes rows read from `table`. Rows that no longer decode are logged and skipped, as
+// the Scylla reads do.
+fn decode<T: DeserializeOwned>(table: &str, data: &[String]) -> Vec<T> {
+    let mut decoded = Vec::with_capacity(data.len());
+    for (i, json) in data.iter().enumerate() {
+        match serde_json::from_str(json) {
+            Ok(row) => decoded.push(row),
+            Err(e) => error!("{} row {} failed to decode: {}", table, i, e),
+        }
+    }
+    decoded
+}
+
+fn text(s: &str) -> Value {
+    Value::Text(s.to_string())
+}
+
+fn int(n: i32) -> Value {
+    Value::Integer(n.into())
+}
+
+fn opt_int(n: Option<i32>) -> Value {
+    n.map_or(Value::Null, int)
+}
+
+#[async_trait]
+impl StatsStore for SqliteStore {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn players(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerStats>> {
+        self.select("player_stats", vec![("team", text(team)), ("year", int(year))]).await
+    }
+
+    async fn player_seasons(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerStats>> {
+        self.select("player_stats_by_pid", vec![("pid", int(pid)), ("year", int(year))]).await
+    }
+
+    async fn write_players(&self, players: &[PlayerStats]) -> StoreResult<WriteReport> {
+        let report = self
+            .upsert(
+                "player_stats",
+                players,
+                |p| (p.team.clone(), p.year, p.player_name.clone()),
+                |p| vec![text(&p.team), opt_int(p.year)],
+                |p| (p.team.clone(), p.year),
+            )
+            .await?;
+        let with_pid: Vec<&PlayerStats> = players.iter().filter(|p| p.pid.is_some()).collect();
+        self.upsert(
+            "player_stats_by_pid",
+            &with_pid,
+            |p| (p.pid, p.year, p.team.clone()),
+            |p| vec![opt_int(p.pid), opt_int(p.year)],
+            |p| (p.pid, p.year),
+        )
+        .await?;
+        Ok(report)
+    }
+
+    async fn team_stats(&self, year: i32) -> StoreResult<Vec<TeamStats>> {
+        self.select("team_stats", vec![("year", int(year))]).await
+    }
+
+    async fn write_team_stats(&self, teams: &[TeamStats]) -> StoreResult<WriteReport> {
+        self.upsert("team_stats", teams, |t| (t.year, t.team.clone()), |t| vec![int(t.year)], |t| t.year).await
+    }
+
+    async fn player_games(&self, pid: i32, year: i32, team: &str) -> StoreResult<Vec<GameStats>> {
+        self.select("game_stats", vec![("pid", int(pid)), ("year", int(year)), ("tt", text(team))]).await
+    }
+
+    async fn team_games(&self, team: &str, year: i32) -> StoreResult<Vec<GameStats>> {
+        self.select("game_stats", vec![("year", int(year)), ("tt", text(team))]).await
+    }
+
+    async fn season_games(&self, year: i32) -> StoreResult<Vec<GameStats>> {
+        self.select("game_stats", vec![("year", int(year))]).await
+    }
+
+    async fn all_games(&self) -> StoreResult<Vec<GameStats>> {
+        self.select("game_stats", vec![]).await
+    }
+
+    async fn write_games(&self, games: &[GameStats]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "game_stats",
+            games,
+            GameStats::key,
+            |g| vec![opt_int(g.pid), opt_int(g.year), text(&g.tt)],
+            |g| (g.pid, g.year, g.tt.clone()),
+        )
+        .await
+    }
+
+    async fn season_averages(&self) -> StoreResult<Vec<PlayerSeasonAverages>> {
+        self.select("player_season_avg_stats", vec![]).await
+    }
+
+    async fn write_season_averages(&self, averages: &[PlayerSeasonAverages]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "player_season_avg_stats",
+            averages,
+            |a| (a.team.clone(), a.year, a.pid),
+            |a| vec![text(&a.team), int(a.year)],
+            |a| (a.team.clone(), a.year),
+        )
+        .await
+    }
+
+    async fn season_percentiles(&self) -> StoreResult<Vec<PlayerSeasonPercentiles>> {
+        self.select("player_season_percentiles", vec![]).await
+    }
+
+    async fn write_season_percentiles(&self, percentiles: &[PlayerSeasonPercentiles]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "player_season_percentiles",
+            percentiles,
+            |p| (p.pid, p.year, p.team.clone()),
+            |p| vec![int(p.pid), int(p.year)],
+            |p| (p.pid, p.year),
+        )
+        .await
+    }
+
+    async fn schedule_game(&self, muid: &str, year: i32) -> StoreResult<Option<ScheduleGame>> {
+        let games = self.select("schedule_games", vec![("muid", text(muid)), ("year", int(year))]).await?;
+        Ok(games.into_iter().next())
+    }
+
+    async fn write_schedule_games(&self, games: &[ScheduleGame]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "schedule_games",
+            games,
+            |g| (g.muid.clone(), g.year),
+            |g| vec![text(&g.muid), int(g.year)],
+            |g| (g.muid.clone(), g.year),
+        )
+        .await
+    }
+
+    async fn four_factors(&self, year: i32) -> StoreResult<Vec<TeamFourFactors>> {
+        self.select("team_four_factors", vec![("year", int(year))]).await
+    }
+
+    async fn write_four_factors(&self, teams: &[TeamFourFactors]) -> StoreResult<WriteReport> {
+        self.upsert("team_four_factors", teams, |f| (f.year, f.team.clone()), |f| vec![int(f.year)], |f| f.year).await
+    }
+
+    async fn player_shot_profiles(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerShotProfile>> {
+        self.select("player_shot_profiles", vec![("team", text(team)), ("year", int(year))]).await
+    }
+
+    async fn write_player_shot_profiles(&self, profiles: &[PlayerShotProfile]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "player_shot_profiles",
+            profiles,
+            |p| (p.team.clone(), p.year, p.pid),
+            |p| vec![text(&p.team), int(p.year)],
+            |p| (p.team.clone(), p.year),
+        )
+        .await
+    }
+
+    async fn team_shooting(&self, year: i32) -> StoreResult<Vec<TeamShooting>> {
+        self.select("team_shooting", vec![("year", int(year))]).await
+    }
+
+    async fn write_team_shooting(&self, teams: &[TeamShooting]) -> StoreResult<WriteReport> {
+        self.upsert("team_shooting", teams, |s| (s.year, s.team.clone()), |s| vec![int(s.year)], |s| s.year).await
+    }
+
+    async fn team_results(&self, team: &str, year: i32) -> StoreResult<Vec<TeamGameResult>> {
+        self.select("team_results", vec![("team", text(team)), ("year", int(year))]).await
+    }
+
+    async fn write_team_results(&self, results: &[TeamGameResult]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "team_results",
+            results,
+            |r| (r.team.clone(), r.year, r.numdate.clone()),
+            |r| vec![text(&r.team), int(r.year)],
+            |r| (r.team.clone(), r.year),
+        )
+        .await
+    }
+
+    async fn player_history(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerSnapshot>> {
+        self.select("player_stats_history", vec![("pid", int(pid)), ("year", int(year))]).await
+    }
+
+    async fn write_player_history(&self, snapshots: &[PlayerSnapshot]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "player_stats_history",
+            snapshots,
+            |s| (s.pid, s.year, s.snapshot_date.clone(), s.team.clone()),
+            |s| vec![int(s.pid), int(s.year)],
+            |s| (s.pid, s.year),
+        )
+        .await
+    }
+
+    async fn team_history(&self, team: &str, year: i32) -> StoreResult<Vec<TeamSnapshot>> {
+        self.select("team_stats_history", vec![("team", text(team)), ("year", int(year))]).await
+    }
+
+    async fn write_team_history(&self, snapshots: &[TeamSnapshot]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "team_stats_history",
+            snapshots,
+            |s| (s.team.clone(), s.year, s.snapshot_date.clone()),
+            |s| vec![text(&s.team), int(s.year)],
+            |s| (s.team.clone(), s.year),
+        )
+        .await
+    }
+
+    async fn player_record(&self, pid: i32) -> StoreResult<Option<PlayerRecord>> {
+        let records = self.select("players", vec![("pid", int(pid))]).await?;
+        Ok(records.into_iter().next())
+    }
+
+    async fn all_player_records(&self) -> StoreResult<Vec<PlayerRecord>> {
+        self.select("players", vec![]).await
+    }
+
+    // SQLite can't merge collections in place, so read the stored records being written
+    // and write them back merged.
+    async fn write_player_records(&self, records: &[PlayerRecord]) -> StoreResult<WriteReport> {
+        let pids = records.iter().map(|r| int(r.pid)).collect();
+        let mut stored: HashMap<i32, PlayerRecord> = self
+            .select_in::<PlayerRecord>("players", "pid", pids)
+            .await?
+            .into_iter()
+            .map(|r| (r.pid, r))
+            .collect();
+        let merged: Vec<PlayerRecord> = records
+            .iter()
+            .map(|record| {
+                let mut merged = stored.remove(&record.pid).unwrap_or_else(|| PlayerRecord { pid: record.pid, ..Default::default() });
+                merged.merge(record.clone());
+                merged
+            })
+            .collect();
+        self.upsert("players", &merged, |r| r.pid, |r| vec![int(r.pid)], |r| r.pid).await
+    }
+
+    async fn teams(&self) -> StoreResult<Vec<TeamRecord>> {
+        self.select("teams", vec![]).await
+    }
+
+    // Merged in Rust for the same reason as `write_player_records`.
+    async fn write_teams(&self, teams: &[TeamRecord]) -> StoreResult<WriteReport> {
+        let team_ids = teams.iter().map(|t| text(&t.team_id)).collect();
+        let mut stored: HashMap<String, TeamRecord> = self
+            .select_in::<TeamRecord>("teams", "team_id", team_ids)
+            .await?
+            .into_iter()
+            .map(|t| (t.team_id.clone(), t))
+            .collect();
+        let merged: Vec<TeamRecord> = teams
+            .iter()
+            .map(|team| match stored.remove(&team.team_id) {
+                Some(mut existing) => {
+                    existing.merge(team.clone());
+                    existing
+                }
+                None => team.clone(),
+            })
+            .collect();
+        let report = self
+            .upsert("teams", &merged, |t| t.team_id.clone(), |t| vec![text(&t.team_id)], |t| t.team_id.clone())
+            .await?;
+        let aliases: Vec<TeamAlias> = merged.iter().flat_map(TeamRecord::alias_rows).collect();
+        self.upsert("team_aliases", &aliases, |a| a.alias.clone(), |a| vec![text(&a.alias)], |a| a.alias.clone())
+            .await?;
+        Ok(report)
+    }
+
+    async fn team_name(&self, alias: &str) -> StoreResult<Option<String>> {
+        let aliases: Vec<TeamAlias> = self.select("team_aliases", vec![("alias", text(&team_key(alias)))]).await?;
+        Ok(aliases.into_iter().next().map(|a| a.name))
+    }
+
+    async fn transfers(&self, year: i32) -> StoreResult<Vec<Transfer>> {
+        self.select("transfers", vec![("year", int(year))]).await
+    }
+
+    async fn write_transfers(&self, transfers: &[Transfer]) -> StoreResult<WriteReport> {
+        self.upsert("transfers", transfers, |t| (t.year, t.pid), |t| vec![int(t.year)], |t| t.year).await
+    }
+
+    async fn clear_transfers(&self, year: i32) -> StoreResult<()> {
+        self.delete("transfers", vec![("year", int(year))]).await
+    }
+
+    async fn conferences(&self, year: i32) -> StoreResult<Vec<ConferenceSummary>> {
+        self.select("conference_stats", vec![("year", int(year))]).await
+    }
+
+    async fn write_conferences(&self, summaries: &[ConferenceSummary]) -> StoreResult<WriteReport> {
+        self.upsert("conference_stats", summaries, |c| (c.year, c.conf.clone()), |c| vec![int(c.year)], |c| c.year).await
+    }
+
+    async fn conference_standings(&self, conf: &str, year: i32) -> StoreResult<Vec<ConferenceStanding>> {
+        self.select("conference_standings", vec![("conf", text(conf)), ("year", int(year))]).await
+    }
+
+    async fn write_conference_standings(&self, standings: &[ConferenceStanding]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "conference_standings",
+            standings,
+            |s| (s.year, s.conf.clone(), s.conf_rank),
+            |s| vec![text(&s.conf), int(s.year)],
+            |s| (s.year, s.conf.clone()),
+        )
+        .await
+    }
+
+    async fn clear_conferences(&self, year: i32) -> StoreResult<()> {
+        self.delete("conference_stats", vec![("year", int(year))]).await?;
+        self.delete("conference_standings", vec![("year", int(year))]).await
+    }
+
+    async fn ingest_runs(&self, run_date: &str) -> StoreResult<Vec<IngestRun>> {
+        self.select("ingest_runs", vec![("run_date", text(run_date))]).await
+    }
+
+    async fn write_ingest_run(&self, run: &IngestRun) -> StoreResult<()> {
+        self.upsert(
+            "ingest_runs",
+            std::slice::from_ref(run),
+            |r| (r.run_date.clone(), r.started_at.clone(), r.source.clone(), r.year),
+            |r| vec![text(&r.run_date)],
+            |r| r.run_date.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn ingest_rejected_rows(&self, run_date: &str, source: &str, year: i32) -> StoreResult<Vec<IngestRejectedRow>> {
+        self.select(
+            "ingest_rejected_rows",
+            vec![("run_date", text(run_date)), ("source", text(source)), ("year", int(year))],
+        )
+        .await
+    }
+
+    async fn write_ingest_rejected_rows(&self, rows: &[IngestRejectedRow]) -> StoreResult<WriteReport> {
+        self.upsert(
+            "ingest_rejected_rows",
+            rows,
+            |r| (r.run_date.clone(), r.source.clone(), r.year, r.started_at.clone(), r.row_index),
+            |r| vec![text(&r.run_date), text(&r.source), int(r.year)],
+            |r| (r.run_date.clone(), r.source.clone(), r.year),
+        )
+        .await
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::ingest::tests::{load_fixtures, load_fixtures_into};
+
+    async fn sqlite_fixtures() -> SqliteStore {
+        let store = SqliteStore::open(Path::new(":memory:")).unwrap();
+        load_fixtures_into(&store).await;
+        store
+    }
+
+    // Rows as JSON in a stable order, so two stores' reads compare whole.
+    fn sorted<T: Serialize>(rows: Vec<T>) -> Vec<serde_json::Value> {
+        let mut rows: Vec<serde_json::Value> = rows.iter().map(|r| serde_json::to_value(r).unwrap()).collect();
+        rows.sort_by_key(|r| r.to_string());
+        rows
+    }
+
+    #[tokio::test]
+    async fn reads_match_the_memory_store() {
+        let (sqlite, memory) = (sqlite_fixtures().await, load_fixtures().await);
+
+        for (team, year) in [("Duke", 2025), ("Duke", 2026), ("North Carolina", 2026)] {
+            let players = sorted(sqlite.players(team, year).await.unwrap());
+            assert!(!players.is_empty(), "no {} players in {}", team, year);
+            assert_eq!(players, sorted(memory.players(team, year).await.unwrap()));
+        }
+        assert_eq!(
+            sorted(sqlite.player_seasons(4242, 2025).await.unwrap()),
+            sorted(memory.player_seasons(4242, 2025).await.unwrap())
+        );
+        assert_eq!(sqlite.player_record(4242).await.unwrap(), memory.player_record(4242).await.unwrap());
+        assert_eq!(sorted(sqlite.all_player_records().await.unwrap()), sorted(memory.all_player_records().await.unwrap()));
+
+        assert_eq!(sorted(sqlite.team_stats(2026).await.unwrap()), sorted(memory.team_stats(2026).await.unwrap()));
+        assert_eq!(sorted(sqlite.teams().await.unwrap()), sorted(memory.teams().await.unwrap()));
+        assert_eq!(sqlite.team_name("north carolina").await.unwrap().as_deref(), Some("North Carolina"));
+
+        assert_eq!(sqlite.season_games(2026).await.unwrap().len(), 7);
+        assert_eq!(sorted(sqlite.season_games(2026).await.unwrap()), sorted(memory.season_games(2026).await.unwrap()));
+        assert_eq!(sorted(sqlite.team_games("Duke", 2026).await.unwrap()), sorted(memory.team_games("Duke", 2026).await.unwrap()));
+        let games = sorted(sqlite.player_games(4242, 2026, "North Carolina").await.unwrap());
+        assert!(!games.is_empty());
+        assert_eq!(games, sorted(memory.player_games(4242, 2026, "North Carolina").await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn registry_writes_merge_into_the_stored_records() {
+        let store = SqliteStore::open(Path::new(":memory:")).unwrap();
+        let season = |year: i32| -> Vec<PlayerRecord> {
+            (1..=MAX_IN_VALUES as i32 + 100)
+                .map(|pid| PlayerRecord { pid, names: [(year, format!("Player {}", pid))].into(), ..Default::default() })
+                .collect()
+        };
+        store.write_player_records(&season(2025)).await.unwrap();
+        store.write_player_records(&season(2026)).await.unwrap();
+
+        let records = store.all_player_records().await.unwrap();
+        assert_eq!(records.len(), MAX_IN_VALUES + 100);
+        assert!(records.iter().all(|r| r.names.keys().copied().eq([2025, 2026])), "{:?}", records[0]);
+    }
+
+    #[tokio::test]
+    async fn clears_replace_only_their_season() {
+        let store = sqlite_fixtures().await;
+        let mut earlier = store.transfers(2026).await.unwrap().remove(0);
+        earlier.year = 2025;
+        store.write_transfers(&[earlier]).await.unwrap();
+
+        store.clear_transfers(2026).await.unwrap();
+        assert!(store.transfers(2026).await.unwrap().is_empty());
+        assert_eq!(store.transfers(2025).await.unwrap().len(), 1);
+
+        assert!(!store.conference_standings("ACC", 2026).await.unwrap().is_empty());
+        store.clear_conferences(2026).await.unwrap();
+        assert!(store.conferences(2026).await.unwrap().is_empty());
+        assert!(store.conference_standings("ACC", 2026).await.unwrap().is_empty());
+        assert!(store.conference_standings("B12", 2026).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+// src/stats_store.rs
+use std::fmt;
+use async_trait::async_trait;
+use scylla::transport::errors::QueryError;
+
+use crate::analytics_types::{PlayerSeasonAverages, PlayerSeasonPercentiles};
+use crate::batch_writer::WriteReport;
+use crate::get_four_factors::TeamFourFactors;
+use crate::get_game_stats::GameStats;
+use crate::get_player_shots::PlayerShotProfile;
+use crate::get_player_stats::PlayerStats;
+use crate::get_schedule::ScheduleGame;
+use crate::get_team_results::TeamGameResult;
+use crate::get_team_shooting::TeamShooting;
+use crate::get_team_stats::TeamStats;
+use crate::ingest_report::{IngestRejectedRow, IngestRun};
+use crate::player_registry::PlayerRecord;
+use crate::team_registry::TeamRecord;
+use crate::transfers::Transfer;
+use crate::conferences::{ConferenceStanding, ConferenceSummary};
+use crate::rating_history::{PlayerSnapshot, TeamSnapshot};
+
+/// Why a `StatsStore` call failed.
+#[derive(Debug)]
+pub enum StoreError {
+    Scylla(QueryError),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+    /// A row couldn't be encoded to or decoded from its stored JSON.
+    Encoding(serde_json::Error),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Scylla(e) => write!(f, "ScyllaDB query failed: {}", e),
+            #[cfg(feature = "sqlite")]
+            StoreError::Sqlite(e) => write!(f, "SQLite query failed: {}", e),
+            StoreError::Encoding(e) => write!(f, "Failed to encode or decode a stored row: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<QueryError> for StoreError {
+    fn from(e: QueryError) -> Self {
+        StoreError::Scylla(e)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        StoreError::Sqlite(e)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(e: serde_json::Error) -> Self {
+        StoreError::Encoding(e)
+    }
+}
+
+pub type StoreResult<T> = Result<T, StoreError>;
+
+/// Where everything mater loads and computes lives: the Barttorvik feeds, the season
+/// analytics built from them and the record of ingest runs.
+///
+/// Writes are upserts keyed like the Scylla tables' primary keys, so writing a row that
+/// is already stored replaces it. Reads return rows in no particular order. Tables kept in
+/// more than one layout (`player_stats`, `game_stats`) are written to every copy, and the
+/// returned `WriteReport` covers the original table.
+#[async_trait]
+pub trait StatsStore: Send + Sync {
+    /// Which database this is, e.g. `scylla:stats@127.0.0.1:9042` or `sqlite:/data/mater.db`.
+    /// The feed archive records what it has loaded into each database under this name.
+    fn name(&self) -> String;
+
+    /// Whether what is written outlives the process. The feed archive only skips a feed
+    /// as already loaded into stores that keep it.
+    fn persistent(&self) -> bool {
+        true
+    }
+
+    /// A team's players for a season (`player_stats`).
+    async fn players(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerStats>>;
+    /// A player's rows for a season, one per team they played for (`player_stats_by_pid`).
+    async fn player_seasons(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerStats>>;
+    async fn write_players(&self, players: &[PlayerStats]) -> StoreResult<WriteReport>;
+
+    /// Every team's ratings for a season (`team_stats`).
+    async fn team_stats(&self, year: i32) -> StoreResult<Vec<TeamStats>>;
+    async fn write_team_stats(&self, teams: &[TeamStats]) -> StoreResult<WriteReport>;
+
+    /// One player's game lines for a team and season (`game_stats`).
+    async fn player_games(&self, pid: i32, year: i32, team: &str) -> StoreResult<Vec<GameStats>>;
+    /// Every game line played for a team in a season (`game_stats_by_team`).
+    async fn team_games(&self, team: &str, year: i32) -> StoreResult<Vec<GameStats>>;
+    /// Every game line of a season.
+    async fn season_games(&self, year: i32) -> StoreResult<Vec<GameStats>>;
+    async fn all_games(&self) -> StoreResult<Vec<GameStats>>;
+    async fn write_games(&self, games: &[GameStats]) -> StoreResult<WriteReport>;
+
+    /// Every player season average (`player_season_avg_stats`).
+    async fn season_averages(&self) -> StoreResult<Vec<PlayerSeasonAverages>>;
+    async fn write_season_averages(&self, averages: &[PlayerSeasonAverages]) -> StoreResult<WriteReport>;
+
+    /// Every player season percentile (`player_season_percentiles`).
+    async fn season_percentiles(&self) -> StoreResult<Vec<PlayerSeasonPercentiles>>;
+    async fn write_season_percentiles(&self, percentiles: &[PlayerSeasonPercentiles]) -> StoreResult<WriteReport>;
+
+    /// One scheduled game by the `muid` carried on game lines (`schedule_games`).
+    async fn schedule_game(&self, muid: &str, year: i32) -> StoreResult<Option<ScheduleGame>>;
+    async fn write_schedule_games(&self, games: &[ScheduleGame]) -> StoreResult<WriteReport>;
+
+    /// Every team's four factors for a season (`team_four_factors`).
+    async fn four_factors(&self, year: i32) -> StoreResult<Vec<TeamFourFactors>>;
+    async fn write_four_factors(&self, teams: &[TeamFourFactors]) -> StoreResult<WriteReport>;
+
+    /// A team's player shot profiles for a season (`player_shot_profiles`).
+    async fn player_shot_profiles(&self, team: &str, year: i32) -> StoreResult<Vec<PlayerShotProfile>>;
+    async fn write_player_shot_profiles(&self, profiles: &[PlayerShotProfile]) -> StoreResult<WriteReport>;
+
+    /// Every team's shooting splits for a season (`team_shooting`).
+    async fn team_shooting(&self, year: i32) -> StoreResult<Vec<TeamShooting>>;
+    async fn write_team_shooting(&self, teams: &[TeamShooting]) -> StoreResult<WriteReport>;
+
+    /// A team's game results for a season (`team_results`).
+    async fn team_results(&self, team: &str, year: i32) -> StoreResult<Vec<TeamGameResult>>;
+    async fn write_team_results(&self, results: &[TeamGameResult]) -> StoreResult<WriteReport>;
+
+    /// Every daily snapshot of a player's season (`player_stats_history`).
+    async fn player_history(&self, pid: i32, year: i32) -> StoreResult<Vec<PlayerSnapshot>>;
+    async fn write_player_history(&self, snapshots: &[PlayerSnapshot]) -> StoreResult<WriteReport>;
+
+    /// Every daily snapshot of a team's season (`team_stats_history`).
+    async fn team_history(&self, team: &str, year: i32) -> StoreResult<Vec<TeamSnapshot>>;
+    async fn write_team_history(&self, snapshots: &[TeamSnapshot]) -> StoreResult<WriteReport>;
+
+    /// A player's registry entry across teams and seasons (`players`).
+    async fn player_record(&self, pid: i32) -> StoreResult<Option<PlayerRecord>>;
+    /// The whole player registry.
+    async fn all_player_records(&self) -> StoreResult<Vec<PlayerRecord>>;
+    /// Merges each record into the stored one, season by season, rather than replacing it.
+    async fn write_player_records(&self, records: &[PlayerRecord]) -> StoreResult<WriteReport>;
+
+    /// The whole team registry (`teams`).
+    async fn teams(&self) -> StoreResult<Vec<TeamRecord>>;
+    /// Merges each record into the stored one (aliases and conferences are appended) and
+    /// indexes its spellings in `team_aliases`.
+    async fn write_teams(&self, teams: &[TeamRecord]) -> StoreResult<WriteReport>;
+    /// The canonical name of the team any spelling or abbreviation refers to (`team_aliases`).
+    async fn team_name(&self, alias: &str) -> StoreResult<Option<String>>;
+
+    /// Every transfer into a season (`transfers`).
+    async fn transfers(&self, year: i32) -> StoreResult<Vec<Transfer>>;
+    async fn write_transfers(&self, transfers: &[Transfer]) -> StoreResult<WriteReport>;
+    /// Deletes every transfer into a season, so a rebuild drops moves no longer detected.
+    async fn clear_transfers(&self, year: i32) -> StoreResult<()>;
+
+    /// Every conference's summary for a season (`conference_stats`).
+    async fn conferences(&self, year: i32) -> StoreResult<Vec<ConferenceSummary>>;
+    async fn write_conferences(&self, summaries: &[ConferenceSummary]) -> StoreResult<WriteReport>;
+    /// A conference's standings for a season (`conference_standings`).
+    async fn conference_standings(&self, conf: &str, year: i32) -> StoreResult<Vec<ConferenceStanding>>;
+    async fn write_conference_standings(&self, standings: &[ConferenceStanding]) -> StoreResult<WriteReport>;
+    /// Deletes a season's conference summaries and every one of its conferences' standings.
+    async fn clear_conferences(&self, year: i32) -> StoreResult<()>;
+
+    /// Every feed run started on a UTC day, YYYY-MM-DD (`ingest_runs`).
+    async fn ingest_runs(&self, run_date: &str) -> StoreResult<Vec<IngestRun>>;
+    async fn write_ingest_run(&self, run: &IngestRun) -> StoreResult<()>;
+    /// Every row a feed's runs on a UTC day rejected for a season (`ingest_rejected_rows`).
+    async fn ingest_rejected_rows(&self, run_date: &str, source: &str, year: i32) -> StoreResult<Vec<IngestRejectedRow>>;
+    async fn write_ingest_rejected_rows(&self, rows: &[IngestRejectedRow]) -> StoreResult<WriteReport>;
+}
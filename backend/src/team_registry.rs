@@ -0,0 +1,345 @@
+// src/team_registry.rs
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::stats_store::{StatsStore, StoreResult};
+
+/// One school in the `teams` registry. Feeds spell some schools differently; every
+/// spelling seen is kept as an alias and ingestion writes `name` in its place.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamRecord {
+    /// Stable slug of the first canonical name, e.g. `nc-state`.
+    pub team_id: String,
+    /// Canonical spelling, written to every table in place of an alias.
+    pub name: String,
+    pub abbreviation: Option<String>,
+    /// Every other spelling seen in a feed or listed in `team_aliases.toml`.
+    pub aliases: BTreeSet<String>,
+    /// Conference by season.
+    pub conferences: BTreeMap<i32, String>,
+}
+
+/// A `team_aliases` row: a normalized spelling and the team it refers to.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct TeamAlias {
+    pub alias: String,
+    pub team_id: String,
+    pub name: String,
+}
+
+/// Compares names ignoring case, punctuation and the `State`/`St.` and `Saint`/`St.`
+/// abbreviations, so `N.C. State`, `NC St.` and `nc state` share a key.
+pub fn team_key(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() => Some(c.to_ascii_lowercase()),
+            '.' | '\'' => None,
+            _ => Some(' '),
+        })
+        .collect();
+    let words: Vec<&str> = cleaned
+        .split_whitespace()
+        .map(|w| match w {
+            "state" | "saint" => "st",
+            w => w,
+        })
+        .collect();
+    words.join(" ")
+}
+
+impl TeamRecord {
+    fn new(name: &str) -> Self {
+        TeamRecord {
+            team_id: team_key(name).replace(' ', "-"),
+            name: name.to_string(),
+            abbreviation: None,
+            aliases: BTreeSet::new(),
+            conferences: BTreeMap::new(),
+        }
+    }
+
+    /// Every spelling that refers to this team, the canonical name first.
+    pub fn spellings(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.name).chain(self.abbreviation.iter()).chain(self.aliases.iter())
+    }
+
+    /// The `team_aliases` rows for this team, one per distinct normalized spelling.
+    pub fn alias_rows(&self) -> Vec<TeamAlias> {
+        let keys: BTreeSet<String> = self.spellings().map(|s| team_key(s)).collect();
+        keys.into_iter()
+            .map(|alias| TeamAlias { alias, team_id: self.team_id.clone(), name: self.name.clone() })
+            .collect()
+    }
+
+    /// Folds `other` (the same team) into this record; its name and abbreviation win.
+    pub fn merge(&mut self, other: TeamRecord) {
+        if other.name != self.name {
+            self.aliases.insert(std::mem::replace(&mut self.name, other.name));
+        }
+        if other.abbreviation.is_some() {
+            self.abbreviation = other.abbreviation;
+        }
+        self.aliases.extend(other.aliases);
+        self.aliases.remove(&self.name);
+        self.conferences.extend(other.conferences);
+    }
+}
+
+#[derive(Deserialize)]
+struct SeedFile {
+    team: Vec<SeedTeam>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeedTeam {
+    name: String,
+    abbreviation: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+/// The registry as ingestion uses it: resolves any spelling to its team, registering
+/// unknown teams as they appear, and tracks which records changed so only those are
+/// written back.
+#[derive(Debug, Default)]
+pub struct TeamRegistry {
+    teams: BTreeMap<String, TeamRecord>,
+    by_key: HashMap<String, String>,
+    changed: BTreeSet<String>,
+}
+
+impl TeamRegistry {
+    /// The seed entries from `team_aliases.toml` merged with the stored registry. Seed
+    /// names and abbreviations win over stored ones, so editing the file renames teams on
+    /// the next ingest.
+    pub async fn load(store: &dyn StatsStore) -> StoreResult<Self> {
+        let mut registry = TeamRegistry::default();
+        for team in store.teams().await? {
+            registry.add(team, false);
+        }
+        let stored = registry.teams.len();
+
+        let seeds: SeedFile = toml::from_str(include_str!("../team_aliases.toml")).expect("valid team_aliases.toml");
+        for seed in seeds.team {
+            let mut record = TeamRecord::new(&seed.name);
+            record.abbreviation = seed.abbreviation;
+            record.aliases.extend(seed.aliases);
+            registry.add(record, true);
+        }
+        info!("Loaded {} registered teams ({} stored)", registry.teams.len(), stored);
+        Ok(registry)
+    }
+
+    // Adds `record`, merging it into the team its name already belongs to or, failing that,
+    // the one team its other spellings do. A record whose spellings point at several teams
+    // is left out, and spellings that belong to another team stay with it, so a seed can't
+    // rename or take aliases from a team it doesn't describe.
+    fn add(&mut self, mut record: TeamRecord, changes: bool) {
+        let matches: BTreeSet<String> = record.spellings().filter_map(|s| self.by_key.get(&team_key(s))).cloned().collect();
+        let existing = match self.by_key.get(&team_key(&record.name)) {
+            Some(team_id) => Some(team_id.clone()),
+            None if matches.len() > 1 => {
+                let teams: Vec<&str> = matches.iter().map(String::as_str).collect();
+                warn!("'{}' matches several registered teams ({}); not merging it", record.name, teams.join(", "));
+                return;
+            }
+            None => matches.into_iter().next(),
+        };
+        if let Some(team_id) = &existing {
+            let taken = |spelling: &String| self.by_key.get(&team_key(spelling)).is_some_and(|id| id != team_id);
+            for spelling in record.aliases.iter().chain(record.abbreviation.iter()).filter(|s| taken(s)) {
+                warn!("'{}' is listed for {} but belongs to {}; keeping it there", spelling, team_id, self.by_key[&team_key(spelling)]);
+            }
+            record.aliases.retain(|alias| !taken(alias));
+            if record.abbreviation.as_ref().is_some_and(taken) {
+                record.abbreviation = None;
+            }
+        }
+        let team_id = match existing {
+            Some(team_id) => {
+                let team = self.teams.get_mut(&team_id).expect("indexed team");
+                let before = team.clone();
+                team.merge(record);
+                if *team == before {
+                    return;
+                }
+                team_id
+            }
+            None => {
+                let team_id = record.team_id.clone();
+                self.teams.insert(team_id.clone(), record);
+                team_id
+            }
+        };
+        let team = &self.teams[&team_id];
+        for spelling in team.spellings() {
+            let key = team_key(spelling);
+            match self.by_key.get(&key) {
+                Some(other) if *other != team_id => warn!("'{}' is listed for both {} and {}", spelling, other, team_id),
+                _ => {
+                    self.by_key.insert(key, team_id.clone());
+                }
+            }
+        }
+        if changes {
+            self.changed.insert(team_id);
+        }
+    }
+
+    /// The canonical spelling of `name`. A spelling not seen before is kept as an alias;
+    /// a team not seen before is registered under it.
+    pub fn canonical(&mut self, name: &str) -> String {
+        let name = name.trim();
+        if name.is_empty() {
+            return String::new();
+        }
+        match self.by_key.get(&team_key(name)).cloned() {
+            Some(team_id) => {
+                let team = self.teams.get_mut(&team_id).expect("indexed team");
+                if team.name != name && team.aliases.insert(name.to_string()) {
+                    self.changed.insert(team_id);
+                }
+                team.name.clone()
+            }
+            None => {
+                self.add(TeamRecord::new(name), true);
+                name.to_string()
+            }
+        }
+    }
+
+    pub fn canonical_opt(&mut self, name: &mut Option<String>) {
+        if let Some(n) = name {
+            *n = self.canonical(n);
+        }
+    }
+
+    /// Records that `team` (already canonical) played in `conf` in `year`.
+    pub fn set_conference(&mut self, team: &str, year: i32, conf: &str) {
+        let conf = conf.trim();
+        if conf.is_empty() {
+            return;
+        }
+        let Some(team_id) = self.by_key.get(&team_key(team)).cloned() else { return };
+        let team = self.teams.get_mut(&team_id).expect("indexed team");
+        if team.conferences.get(&year).map(String::as_str) != Some(conf) {
+            team.conferences.insert(year, conf.to_string());
+            self.changed.insert(team_id);
+        }
+    }
+
+    /// Teams added or changed since `load`.
+    pub fn changed(&self) -> Vec<TeamRecord> {
+        self.changed.iter().filter_map(|id| self.teams.get(id)).cloned().collect()
+    }
+}
+
+/// Writes `teams` and their `team_aliases` rows. Aliases and conferences are appended to
+/// what is stored, as `TeamRecord::merge` does.
+pub async fn insert_teams(session: &Session, teams: &[TeamRecord]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    UPDATE teams SET name = ?, abbreviation = ?, aliases = aliases + ?, conferences = conferences + ?
+    WHERE team_id = ?
+"#;
+    let rows: Vec<_> = teams
+        .iter()
+        .map(|t| (&t.name, &t.abbreviation, &t.aliases, &t.conferences, &t.team_id))
+        .collect();
+    let report = write_batched(session, "teams", query, &rows, |t| t.4.clone()).await?;
+
+    let aliases: Vec<TeamAlias> = teams.iter().flat_map(TeamRecord::alias_rows).collect();
+    let alias_query = "INSERT INTO team_aliases (alias, team_id, name) VALUES (?, ?, ?)";
+    write_batched(session, "team_aliases", alias_query, &aliases, |a| a.alias.clone()).await?;
+    Ok(report)
+}
+
+/// Fetches the whole registry (a few hundred rows).
+pub async fn get_teams_from_db(session: &Session) -> Result<Vec<TeamRecord>, QueryError> {
+    let result = session
+        .query("SELECT team_id, name, abbreviation, aliases, conferences FROM teams", &[])
+        .await?;
+    let mut teams = Vec::new();
+    for (i, row) in result.rows.unwrap_or_default().into_iter().enumerate() {
+        // Empty collections read back as null.
+        type Row = (String, String, Option<String>, Option<BTreeSet<String>>, Option<BTreeMap<i32, String>>);
+        match row.into_typed::<Row>() {
+            Ok((team_id, name, abbreviation, aliases, conferences)) => teams.push(TeamRecord {
+                team_id,
+                name,
+                abbreviation,
+                aliases: aliases.unwrap_or_default(),
+                conferences: conferences.unwrap_or_default(),
+            }),
+            Err(e) => error!("Team registry row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(teams)
+}
+
+/// The canonical name of the team `alias` refers to, if any.
+pub async fn get_team_name_from_db(session: &Session, alias: &str) -> Result<Option<String>, QueryError> {
+    let prepared = session.prepare("SELECT alias, team_id, name FROM team_aliases WHERE alias = ?").await?;
+    let result = session.execute(&prepared, (team_key(alias),)).await?;
+    Ok(result.rows.unwrap_or_default().into_iter().find_map(|row| TeamAlias::from_row(row).ok()).map(|a| a.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_store::MemoryStore;
+
+    #[test]
+    fn seeds_spell_each_team_distinctly() {
+        let seeds: SeedFile = toml::from_str(include_str!("../team_aliases.toml")).unwrap();
+        let mut owners: HashMap<String, &str> = HashMap::new();
+        for seed in &seeds.team {
+            for spelling in std::iter::once(&seed.name).chain(seed.abbreviation.iter()).chain(seed.aliases.iter()) {
+                if let Some(other) = owners.insert(team_key(spelling), &seed.name)
+                    && other != seed.name
+                {
+                    panic!("'{}' is listed for both {} and {}", spelling, other, seed.name);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn seeds_rename_only_the_team_they_describe() {
+        let store = MemoryStore::default();
+        let stored = ["UConn", "Miami", "Miami (FL)"].map(TeamRecord::new);
+        store.write_teams(&stored).await.unwrap();
+
+        let mut registry = TeamRegistry::load(&store).await.unwrap();
+        // The seed lists UConn as an alias of Connecticut, so that team takes the seed's name.
+        assert_eq!(registry.canonical("UConn"), "Connecticut");
+        // "Miami" is a Miami FL alias in the seeds, but here it is already a team of its own.
+        assert_eq!(registry.canonical("Miami (FL)"), "Miami FL");
+        assert_eq!(registry.canonical("Miami"), "Miami");
+        for team in registry.changed() {
+            for row in team.alias_rows().iter().filter(|row| row.alias == "miami") {
+                assert_eq!(row.team_id, "miami", "{} took the 'Miami' alias", team.name);
+            }
+        }
+    }
+
+    #[test]
+    fn a_record_matching_several_teams_is_left_out() {
+        let mut registry = TeamRegistry::default();
+        registry.add(TeamRecord::new("Loyola Chicago"), false);
+        registry.add(TeamRecord::new("Loyola MD"), false);
+
+        let mut ambiguous = TeamRecord::new("Loyola");
+        ambiguous.aliases.extend(["Loyola Chicago".to_string(), "Loyola MD".to_string()]);
+        registry.add(ambiguous, true);
+
+        assert!(registry.changed().is_empty());
+        assert_eq!(registry.canonical("Loyola Chicago"), "Loyola Chicago");
+        assert_eq!(registry.canonical("Loyola MD"), "Loyola MD");
+    }
+}
@@ -0,0 +1,207 @@
+// src/transfers.rs
+use std::collections::BTreeSet;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use scylla::{FromRow, SerializeRow, Session};
+use scylla::transport::errors::QueryError;
+
+use crate::batch_writer::{WriteReport, write_batched};
+use crate::get_player_stats::PlayerStats;
+use crate::player_registry::PlayerRecord;
+use crate::stats_store::{StatsStore, StoreResult};
+
+/// A player who changed teams between seasons, with their numbers either side of the
+/// move (`transfers`). `pre_` is the last season at `from_team`, `post_` the first at
+/// `to_team`; each `_delta` is post minus pre.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, SerializeRow)]
+pub struct Transfer {
+    /// First season at the new team.
+    pub year: i32,
+    pub pid: i32,
+    pub player_name: String,
+    pub from_team: String,
+    pub to_team: String,
+    pub from_conf: Option<String>,
+    pub to_conf: Option<String>,
+    pub pre_min_per: Option<f64>,
+    pub post_min_per: Option<f64>,
+    pub pre_bpm: Option<f64>,
+    pub post_bpm: Option<f64>,
+    pub bpm_delta: Option<f64>,
+    pub pre_usg: Option<f64>,
+    pub post_usg: Option<f64>,
+    pub usg_delta: Option<f64>,
+    pub pre_o_rtg: Option<f64>,
+    pub post_o_rtg: Option<f64>,
+    pub o_rtg_delta: Option<f64>,
+    pub pre_ts_per: Option<f64>,
+    pub post_ts_per: Option<f64>,
+    pub ts_per_delta: Option<f64>,
+}
+
+fn delta(pre: Option<f64>, post: Option<f64>) -> Option<f64> {
+    Some(post? - pre?)
+}
+
+impl Transfer {
+    fn new(year: i32, pid: i32, name: &str, from_team: &str, to_team: &str, pre: Option<&PlayerStats>, post: Option<&PlayerStats>) -> Self {
+        let stat = |row: Option<&PlayerStats>, f: fn(&PlayerStats) -> Option<f64>| row.and_then(f);
+        let (pre_bpm, post_bpm) = (stat(pre, |p| p.bpm), stat(post, |p| p.bpm));
+        let (pre_usg, post_usg) = (stat(pre, |p| p.usg), stat(post, |p| p.usg));
+        let (pre_o_rtg, post_o_rtg) = (stat(pre, |p| p.o_rtg), stat(post, |p| p.o_rtg));
+        let (pre_ts_per, post_ts_per) = (stat(pre, |p| p.ts_per), stat(post, |p| p.ts_per));
+        Transfer {
+            year,
+            pid,
+            player_name: name.to_string(),
+            from_team: from_team.to_string(),
+            to_team: to_team.to_string(),
+            from_conf: pre.map(|p| p.conf.clone()),
+            to_conf: post.map(|p| p.conf.clone()),
+            pre_min_per: stat(pre, |p| p.min_per),
+            post_min_per: stat(post, |p| p.min_per),
+            pre_bpm,
+            post_bpm,
+            bpm_delta: delta(pre_bpm, post_bpm),
+            pre_usg,
+            post_usg,
+            usg_delta: delta(pre_usg, post_usg),
+            pre_o_rtg,
+            post_o_rtg,
+            o_rtg_delta: delta(pre_o_rtg, post_o_rtg),
+            pre_ts_per,
+            post_ts_per,
+            ts_per_delta: delta(pre_ts_per, post_ts_per),
+        }
+    }
+}
+
+// The player's row for `team` among their rows for a season.
+async fn season_row(store: &dyn StatsStore, pid: i32, year: i32, team: &str) -> StoreResult<Option<PlayerStats>> {
+    Ok(store.player_seasons(pid, year).await?.into_iter().find(|p| p.team == team))
+}
+
+/// Finds every player in the registry listed in `year` with a team they weren't on in
+/// `year - 1`, and pairs their `player_stats` rows from both seasons. A player who moved
+/// mid-season is on both teams that season: one who ends it with a team they join for
+/// the next is no transfer, one who moves during the next season transfers from their
+/// earlier team.
+pub async fn detect_transfers(store: &dyn StatsStore, records: &[PlayerRecord], year: i32) -> StoreResult<Vec<Transfer>> {
+    let mut transfers = Vec::new();
+    for record in records {
+        let (Some(before), Some(after)) = (record.teams_in(year - 1), record.teams_in(year)) else { continue };
+        let Some(to_team) = after.iter().find(|t| !before.contains(*t)) else { continue };
+        let Some(from_team) = before.iter().find(|t| !after.contains(*t)).or_else(|| before.first()) else { continue };
+        let pre = season_row(store, record.pid, year - 1, from_team).await?;
+        let post = season_row(store, record.pid, year, to_team).await?;
+        let name = record.names.get(&year).or_else(|| record.names.values().next_back()).map_or("", String::as_str);
+        transfers.push(Transfer::new(year, record.pid, name, from_team, to_team, pre.as_ref(), post.as_ref()));
+    }
+    Ok(transfers)
+}
+
+/// Rebuilds `transfers` for `seasons` (every season in the player registry when `None`),
+/// replacing whatever each season held before. Runs after players loads, since a
+/// season's registry entries are what link a player's teams across years.
+pub async fn refresh_transfers(store: &dyn StatsStore, seasons: Option<&[i32]>) -> StoreResult<()> {
+    let records = store.all_player_records().await?;
+    let seasons: BTreeSet<i32> = match seasons {
+        Some(seasons) => seasons.iter().copied().collect(),
+        None => records.iter().flat_map(|r| r.teams.keys().copied()).collect(),
+    };
+    for year in seasons {
+        let transfers = detect_transfers(store, &records, year).await?;
+        info!("Found {} transfers into the {} season", transfers.len(), year);
+        store.clear_transfers(year).await?;
+        if !transfers.is_empty() {
+            store.write_transfers(&transfers).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn insert_transfers(session: &Session, transfers: &[Transfer]) -> Result<WriteReport, QueryError> {
+    let query = r#"
+    INSERT INTO transfers (
+        year, pid, player_name, from_team, to_team, from_conf, to_conf, pre_min_per, post_min_per,
+        pre_bpm, post_bpm, bpm_delta, pre_usg, post_usg, usg_delta, pre_o_rtg, post_o_rtg, o_rtg_delta,
+        pre_ts_per, post_ts_per, ts_per_delta
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+    write_batched(session, "transfers", query, transfers, |t| t.year).await
+}
+
+/// Deletes every transfer into a season (one partition).
+pub async fn delete_transfers(session: &Session, year: i32) -> Result<(), QueryError> {
+    let prepared = session.prepare("DELETE FROM transfers WHERE year = ?").await?;
+    session.execute(&prepared, (year,)).await?;
+    Ok(())
+}
+
+/// Fetches every transfer into a season (one partition).
+pub async fn get_transfers_from_db(session: &Session, year: i32) -> Result<Vec<Transfer>, QueryError> {
+    let query = r#"
+    SELECT year, pid, player_name, from_team, to_team, from_conf, to_conf, pre_min_per, post_min_per,
+           pre_bpm, post_bpm, bpm_delta, pre_usg, post_usg, usg_delta, pre_o_rtg, post_o_rtg, o_rtg_delta,
+           pre_ts_per, post_ts_per, ts_per_delta
+    FROM transfers WHERE year = ?
+"#;
+
+    let prepared = session.prepare(query).await?;
+    let result = session.execute(&prepared, (year,)).await?;
+    let rows = result.rows.unwrap_or_default();
+
+    let mut transfers = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        match Transfer::from_row(row) {
+            Ok(t) => transfers.push(t),
+            Err(e) => error!("Transfer row {} failed to convert: {}", i, e),
+        }
+    }
+    Ok(transfers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::tests::load_fixtures;
+
+    #[tokio::test]
+    async fn refresh_replaces_the_season() {
+        let store = load_fixtures().await;
+        let mut stale = store.transfers(2026).await.unwrap().remove(0);
+        stale.pid = 9999;
+        store.write_transfers(&[stale]).await.unwrap();
+
+        refresh_transfers(&store, Some(&[2026])).await.unwrap();
+        let pids: Vec<i32> = store.transfers(2026).await.unwrap().iter().map(|t| t.pid).collect();
+        assert_eq!(pids, [4242]);
+    }
+
+    #[tokio::test]
+    async fn mid_season_moves_keep_every_team() {
+        let store = load_fixtures().await;
+        // Sam Roe also played for Duke in 2025 before settling at North Carolina, and Jane
+        // Doe returned to Duke partway through 2026.
+        let mut sam = store.player_seasons(5151, 2025).await.unwrap().remove(0);
+        sam.team = "Duke".to_string();
+        let mut jane = store.player_seasons(4242, 2026).await.unwrap().remove(0);
+        jane.team = "Duke".to_string();
+        let mut rows: Vec<PlayerStats> = Vec::new();
+        for (pid, year) in [(5151, 2025), (4242, 2026)] {
+            rows.extend(store.player_seasons(pid, year).await.unwrap());
+        }
+        rows.extend([sam, jane]);
+        store.write_players(&rows).await.unwrap();
+        store.write_player_records(&crate::player_registry::player_records(&rows)).await.unwrap();
+
+        let sam = store.player_record(5151).await.unwrap().unwrap();
+        assert_eq!(sam.teams_in(2025).unwrap().len(), 2);
+
+        let records = store.all_player_records().await.unwrap();
+        let transfers = detect_transfers(&store, &records, 2026).await.unwrap();
+        let moves: Vec<(i32, &str, &str)> =
+            transfers.iter().map(|t| (t.pid, t.from_team.as_str(), t.to_team.as_str())).collect();
+        assert_eq!(moves, [(4242, "Duke", "North Carolina")]);
+    }
+}
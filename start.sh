@@ -108,7 +108,7 @@ start_backend() {
     fi
 
     # Start backend in background
-    cargo run > "$SCRIPT_DIR/.logs/backend.log" 2>&1 &
+    cargo run -- serve > "$SCRIPT_DIR/.logs/backend.log" 2>&1 &
     local pid=$!
     echo $pid > "$PID_DIR/backend.pid"
 